@@ -0,0 +1,96 @@
+use std::{fmt::Write, path::Path};
+
+/// Renders each error in `error` like a rustc diagnostic, pointing at the
+/// line of `source` its span starts on.
+pub fn render(path: &Path, source: &str, error: &syn::Error) -> String {
+    let mut out = String::new();
+    for error in error {
+        let start = error.span().start();
+        let end = error.span().end();
+        let line = source
+            .lines()
+            .nth(start.line.saturating_sub(1))
+            .unwrap_or("");
+
+        let prefix: String = line
+            .chars()
+            .take(start.column)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let end_column = if end.line == start.line {
+            end.column
+        } else {
+            line.chars().count()
+        };
+        let carets = "^".repeat(end_column.saturating_sub(start.column).max(1));
+
+        let line_number = start.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let _ = writeln!(out, "error: {}", error);
+        let _ = writeln!(
+            out,
+            "{}--> {}:{}:{}",
+            gutter,
+            path.display(),
+            start.line,
+            start.column + 1
+        );
+        let _ = writeln!(out, "{} |", gutter);
+        let _ = writeln!(out, "{} | {}", line_number, line);
+        let _ = writeln!(out, "{} | {}{}", gutter, prefix, carets);
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns whether `source` contains a `//` or `/* */` comment outside of
+/// string and character literals.
+pub fn has_comments(source: &str) -> bool {
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if matches!(chars.peek(), Some('/' | '*')) => return true,
+            '"' | '\'' => {
+                while let Some(next) = chars.next() {
+                    if next == '\\' {
+                        chars.next();
+                    } else if next == c {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cycle_schema::parse::Scheme;
+    use std::str::FromStr;
+
+    #[test]
+    fn renders_errors() {
+        let source = "sch \"a\";\n\n@ver(1)\nstruct S {\n    a: u8 bytes: u8,\n}\n";
+        let tokens = proc_macro2::TokenStream::from_str(source).unwrap();
+        let error = syn::parse2::<Scheme>(tokens).unwrap_err();
+
+        assert_eq!(
+            render(Path::new("a.cycle"), source, &error),
+            "error: expected `,`\n \
+             --> a.cycle:5:11\n  \
+              |\n\
+             5 |     a: u8 bytes: u8,\n  \
+              |           ^^^^^\n\n"
+        );
+    }
+
+    #[test]
+    fn finds_comments() {
+        assert!(has_comments("sch \"a\"; // comment"));
+        assert!(has_comments("/* comment */ sch \"a\";"));
+        assert!(!has_comments("sch \"a//b\"; const C: char = '/';"));
+    }
+}
@@ -0,0 +1,398 @@
+mod diagnostic;
+
+use std::{
+    env, fs,
+    path::{Component, Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+};
+
+use cycle_schema::{
+    diff::{diff, Compatibility},
+    export::{docs, json_schema, proto, typescript},
+    format::format,
+    parse::{Scheme, Use},
+    validate::{validate, validate_externs},
+};
+
+const USAGE: &str = "\
+usage: cycle check <file>...
+       cycle fmt [--check] <file>...
+       cycle diff <old> <new>
+       cycle export --format proto|jsonschema|ts|markdown|html [--out <dir>] <file> [<used>...]
+
+A scheme brought in with `use a::b;` is looked up among the other files
+given, as the scheme whose `sch` name ends with `a/b`, ignoring leading
+`crate`, `self` and `super` segments. References into schemes that are
+not among the files given are not checked.";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Runs the command in `args`, returning whether it succeeded. Errors are
+/// usage or I/O errors.
+fn run(args: &[String]) -> Result<bool, String> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(USAGE.to_string());
+    };
+    match command.as_str() {
+        "check" => check(args),
+        "fmt" => fmt(args),
+        "diff" => match args {
+            [old, new] => diff_files(Path::new(old), Path::new(new)),
+            _ => Err(USAGE.to_string()),
+        },
+        "export" => export(args),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(true)
+        }
+        _ => Err(format!("unknown command `{}`\n{}", command, USAGE)),
+    }
+}
+
+/// A schema file and its contents.
+struct Source {
+    path: PathBuf,
+    text: String,
+}
+
+impl Source {
+    fn read(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| format!("could not read {}: {}", path.display(), err))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            text,
+        })
+    }
+
+    fn parse(&self) -> syn::Result<Scheme> {
+        let tokens = proc_macro2::TokenStream::from_str(&self.text)
+            .map_err(|err| syn::Error::new(err.span(), err))?;
+        syn::parse2(tokens)
+    }
+
+    /// Prints `error` to stderr, pointing into this file.
+    fn report(&self, error: &syn::Error) {
+        eprint!("{}", diagnostic::render(&self.path, &self.text, error));
+    }
+}
+
+/// Reads and parses the file at `path`, printing any syntax errors.
+fn load(path: &Path) -> Result<Option<(Source, Scheme)>, String> {
+    let source = Source::read(path)?;
+    match source.parse() {
+        Ok(scheme) => Ok(Some((source, scheme))),
+        Err(err) => {
+            source.report(&err);
+            Ok(None)
+        }
+    }
+}
+
+/// Returns the scheme among `loaded` that `use_` brings into scope: the one
+/// whose name ends with the use path, without leading `crate`, `self` or
+/// `super` segments.
+fn find_scheme<'a>(loaded: &'a [(Source, Scheme)], use_: &Use) -> Option<&'a Scheme> {
+    let segments: Vec<&str> = use_
+        .segments
+        .iter()
+        .map(String::as_str)
+        .skip_while(|segment| matches!(*segment, "crate" | "self" | "super"))
+        .collect();
+    if segments.is_empty() {
+        return None;
+    }
+    loaded.iter().map(|(_, scheme)| scheme).find(|scheme| {
+        scheme
+            .name
+            .split('/')
+            .collect::<Vec<_>>()
+            .ends_with(&segments)
+    })
+}
+
+/// Parses and validates every file. References into schemes brought in with
+/// `use` are validated when one of the files declares the used scheme.
+fn check(paths: &[String]) -> Result<bool, String> {
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let mut ok = true;
+    let mut loaded = Vec::new();
+    for path in paths {
+        match load(Path::new(path))? {
+            Some(loaded_file) => loaded.push(loaded_file),
+            None => ok = false,
+        }
+    }
+
+    for (source, scheme) in &loaded {
+        let result = validate(scheme)
+            .and_then(|()| validate_externs(scheme, |use_| find_scheme(&loaded, use_)));
+        if let Err(err) = result {
+            source.report(&err);
+            ok = false;
+        }
+    }
+    Ok(ok)
+}
+
+/// Rewrites every file in canonical format, or with `--check` lists the
+/// files that are not formatted.
+///
+/// Comments are not part of a parsed scheme, so files with comments are
+/// left alone rather than losing them.
+fn fmt(args: &[String]) -> Result<bool, String> {
+    let (check_only, paths) = match args.split_first() {
+        Some((flag, paths)) if flag == "--check" => (true, paths),
+        _ => (false, args),
+    };
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let mut ok = true;
+    for path in paths {
+        let Some((source, scheme)) = load(Path::new(path))? else {
+            ok = false;
+            continue;
+        };
+        let formatted = match format(&scheme) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                source.report(&err);
+                ok = false;
+                continue;
+            }
+        };
+        if formatted == source.text {
+            continue;
+        }
+
+        if diagnostic::has_comments(&source.text) {
+            eprintln!(
+                "{}: not formatted, as formatting would drop its comments",
+                path
+            );
+            ok = false;
+        } else if check_only {
+            println!("{} is not formatted", path);
+            ok = false;
+        } else {
+            fs::write(path, formatted)
+                .map_err(|err| format!("could not write {}: {}", path, err))?;
+        }
+    }
+    Ok(ok)
+}
+
+/// Prints the changes from `old` to `new`, failing if any are breaking.
+fn diff_files(old: &Path, new: &Path) -> Result<bool, String> {
+    let (Some((_, old_scheme)), Some((_, new_scheme))) = (load(old)?, load(new)?) else {
+        return Ok(false);
+    };
+
+    let changes = diff(&old_scheme, &new_scheme);
+    for change in &changes {
+        println!("{}", change);
+    }
+    Ok(changes
+        .iter()
+        .all(|change| change.compatibility == Compatibility::Compatible))
+}
+
+/// Exports a scheme to stdout, or into `--out`. JSON Schema and
+/// documentation exports have a file per type, so they are always written
+/// to files, into the current directory by default.
+///
+/// Files after the first are the schemes it uses, so that references into
+/// them are named after their `sch` names rather than their use paths. The
+/// scheme is validated like `check` validates it before it is exported.
+fn export(args: &[String]) -> Result<bool, String> {
+    let mut format = None;
+    let mut out = None;
+    let mut paths = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next(),
+            "--out" => out = args.next().map(PathBuf::from),
+            _ => paths.push(arg),
+        }
+    }
+    let (Some(format), Some((path, used_paths))) = (format, paths.split_first()) else {
+        return Err(USAGE.to_string());
+    };
+
+    let extension = match format.as_str() {
+        "proto" => "proto",
+        "ts" => "d.ts",
+        "jsonschema" => "json",
+        "markdown" => "md",
+        "html" => "html",
+        _ => return Err(format!("unknown export format `{}`\n{}", format, USAGE)),
+    };
+    let Some((source, scheme)) = load(Path::new(path))? else {
+        return Ok(false);
+    };
+    let mut used = Vec::new();
+    for used_path in used_paths {
+        match load(Path::new(used_path))? {
+            Some(loaded_file) => used.push(loaded_file),
+            None => return Ok(false),
+        }
+    }
+    let used_scheme = |use_: &Use| find_scheme(&used, use_);
+    let result = validate(&scheme).and_then(|()| validate_externs(&scheme, used_scheme));
+    if let Err(err) = result {
+        source.report(&err);
+        return Ok(false);
+    }
+
+    let documents = match format.as_str() {
+        "proto" => match proto::export(&scheme, used_scheme) {
+            Ok(contents) => vec![(format!("{}.{}", scheme.name, extension), contents)],
+            Err(err) => {
+                source.report(&err);
+                return Ok(false);
+            }
+        },
+        "ts" => match typescript::export(&scheme, used_scheme) {
+            Ok(contents) => vec![(format!("{}.{}", scheme.name, extension), contents)],
+            Err(err) => {
+                source.report(&err);
+                return Ok(false);
+            }
+        },
+        "jsonschema" => match json_schema::export(&scheme, used_scheme) {
+            Ok(documents) => documents
+                .into_iter()
+                .map(|document| (document.path, document.contents))
+                .collect(),
+            Err(err) => {
+                source.report(&err);
+                return Ok(false);
+            }
+        },
+        "markdown" => docs::markdown(&scheme, used_scheme)
+            .into_iter()
+            .map(|document| (document.path, document.contents))
+            .collect(),
+        _ => docs::html(&scheme, used_scheme)
+            .into_iter()
+            .map(|document| (document.path, document.contents))
+            .collect(),
+    };
+
+    let out = match out {
+        Some(out) => out,
+        None if matches!(format.as_str(), "proto" | "ts") => {
+            for (_, contents) in documents {
+                print!("{}", contents);
+            }
+            return Ok(true);
+        }
+        None => PathBuf::from("."),
+    };
+    // Check every path before writing any, so nothing is written outside of
+    // `out` or left half exported.
+    let documents = documents
+        .into_iter()
+        .map(|(document_path, contents)| Ok((out_path(&out, &document_path)?, contents)))
+        .collect::<Result<Vec<_>, String>>()?;
+    for (document_path, contents) in documents {
+        if let Some(parent) = document_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("could not create {}: {}", parent.display(), err))?;
+        }
+        fs::write(&document_path, contents)
+            .map_err(|err| format!("could not write {}: {}", document_path.display(), err))?;
+    }
+    Ok(true)
+}
+
+/// Returns the path in `out` to write the exported document `path` to.
+/// Document paths start with the scheme's `sch` name, so paths that would
+/// lead outside of `out` are errors.
+fn out_path(out: &Path, path: &str) -> Result<PathBuf, String> {
+    let is_relative = Path::new(path)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !is_relative {
+        return Err(format!(
+            "cannot export to {}, as it is not a relative path inside of {}",
+            path,
+            out.display()
+        ));
+    }
+    Ok(out.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loaded(text: &str) -> (Source, Scheme) {
+        let source = Source {
+            path: PathBuf::from("test.cycle"),
+            text: text.to_string(),
+        };
+        let scheme = source.parse().unwrap();
+        (source, scheme)
+    }
+
+    fn use_of(segments: &[&str]) -> Use {
+        Use {
+            segments: segments.iter().map(|segment| segment.to_string()).collect(),
+            alias: None,
+        }
+    }
+
+    #[test]
+    fn finds_used_schemes() {
+        let loaded = [loaded("sch \"org/common\";"), loaded("sch \"org/users\";")];
+        let found = |segments: &[&str]| {
+            find_scheme(&loaded, &use_of(segments)).map(|scheme| scheme.name.as_str())
+        };
+
+        assert_eq!(found(&["crate", "common"]), Some("org/common"));
+        assert_eq!(found(&["super", "org", "users"]), Some("org/users"));
+        assert_eq!(found(&["crate", "other"]), None);
+        assert_eq!(found(&["crate"]), None);
+        assert_eq!(found(&["super", "self"]), None);
+    }
+
+    #[test]
+    fn keeps_exported_documents_inside_out() {
+        let out = Path::new("out");
+        assert_eq!(
+            out_path(out, "org/users/index.md"),
+            Ok(PathBuf::from("out/org/users/index.md"))
+        );
+        for path in [
+            "../users/index.md",
+            "org/../../index.md",
+            "/etc/users.proto",
+        ] {
+            assert_eq!(
+                out_path(out, path),
+                Err(format!(
+                    "cannot export to {}, as it is not a relative path inside of out",
+                    path
+                ))
+            );
+        }
+    }
+}
@@ -0,0 +1,604 @@
+use std::fmt;
+
+use crate::{
+    format::format_field_type,
+    parse::{
+        Enum, EnumField, FieldType, FlagsField, MajorVersion, MinorVersion, Object, Scheme,
+        StructBody, StructItem, TupleField, Type, Union,
+    },
+    resolve::{resolve, Member, Resolved},
+};
+
+/// Whether peers still using the old scheme can exchange values with peers
+/// using the new one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Compatibility {
+    Compatible,
+    Breaking,
+}
+
+/// A difference between two versions of a scheme.
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub compatibility: Compatibility,
+    /// The item that changed, e.g. `User@ver(1).email`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let compatibility = match self.compatibility {
+            Compatibility::Compatible => "compatible",
+            Compatibility::Breaking => "breaking",
+        };
+        write!(f, "{}: {}: {}", compatibility, self.path, self.message)
+    }
+}
+
+/// Lists the changes from `old` to `new`, in the order of the items of `old`
+/// followed by the items only `new` declares.
+///
+/// A published type version may only evolve through minor versions: adding
+/// struct, tuple and flags fields with `@add`, and removing union, enum and
+/// flags fields with `@rem`. Adding a type version or a constant is
+/// compatible. Any other change to an existing item is breaking, including
+/// changing an alias, as aliases are inlined into the types using them.
+pub fn diff(old: &Scheme, new: &Scheme) -> Vec<Change> {
+    let mut differ = Differ {
+        old,
+        new,
+        changes: Vec::new(),
+    };
+    if old.name != new.name {
+        differ.breaking(&new.name, format!("scheme was renamed from {:?}", old.name));
+    }
+    differ.consts();
+    differ.aliases();
+    differ.types();
+    differ.changes
+}
+
+struct Differ<'a> {
+    old: &'a Scheme,
+    new: &'a Scheme,
+    changes: Vec<Change>,
+}
+
+impl<'a> Differ<'a> {
+    fn push(&mut self, compatibility: Compatibility, path: &str, message: String) {
+        self.changes.push(Change {
+            compatibility,
+            path: path.to_string(),
+            message,
+        });
+    }
+
+    fn breaking(&mut self, path: &str, message: String) {
+        self.push(Compatibility::Breaking, path, message);
+    }
+
+    fn compatible(&mut self, path: &str, message: String) {
+        self.push(Compatibility::Compatible, path, message);
+    }
+
+    fn consts(&mut self) {
+        for old_const in &self.old.consts {
+            match self.new.consts.iter().find(|c| c.name == old_const.name) {
+                None => self.breaking(&old_const.name, "constant was removed".to_string()),
+                Some(new_const) if new_const != old_const => self.breaking(
+                    &old_const.name,
+                    "constant changed its type or value".to_string(),
+                ),
+                Some(_) => {}
+            }
+        }
+        for new_const in &self.new.consts {
+            if !self.old.consts.iter().any(|c| c.name == new_const.name) {
+                self.compatible(&new_const.name, "constant was added".to_string());
+            }
+        }
+    }
+
+    fn aliases(&mut self) {
+        for old_alias in &self.old.aliases {
+            match self.new.aliases.iter().find(|a| a.name == old_alias.name) {
+                None => self.breaking(&old_alias.name, "alias was removed".to_string()),
+                Some(new_alias) if new_alias.field_type != old_alias.field_type => self.breaking(
+                    &old_alias.name,
+                    format!(
+                        "alias changed from `{}` to `{}`",
+                        format_field_type(&old_alias.field_type),
+                        format_field_type(&new_alias.field_type)
+                    ),
+                ),
+                Some(_) => {}
+            }
+        }
+        for new_alias in &self.new.aliases {
+            if !self.old.aliases.iter().any(|a| a.name == new_alias.name) {
+                self.compatible(&new_alias.name, "alias was added".to_string());
+            }
+        }
+    }
+
+    fn types(&mut self) {
+        let find = |scheme: &'a Scheme, type_def: &Type| {
+            scheme.types.iter().find(|other| {
+                other.name() == type_def.name() && other.version() == type_def.version()
+            })
+        };
+
+        for old_type in &self.old.types {
+            let path = type_path(old_type);
+            match find(self.new, old_type) {
+                None => self.breaking(&path, "type version was removed".to_string()),
+                Some(new_type) => self.type_def(&path, old_type, new_type),
+            }
+        }
+        for new_type in &self.new.types {
+            if find(self.old, new_type).is_none() {
+                self.compatible(&type_path(new_type), "type version was added".to_string());
+            }
+        }
+    }
+
+    fn type_def(&mut self, path: &str, old_type: &'a Type, new_type: &'a Type) {
+        if kind(old_type) != kind(new_type) {
+            self.breaking(
+                path,
+                format!("changed from {} to {}", kind(old_type), kind(new_type)),
+            );
+            return;
+        }
+        if old_type.generics().len() != new_type.generics().len() {
+            self.breaking(path, "changed its number of generic parameters".to_string());
+        }
+
+        match (old_type, new_type) {
+            (
+                Type::Struct(old_struct) | Type::Object(Object::Struct(old_struct)),
+                Type::Struct(new_struct) | Type::Object(Object::Struct(new_struct)),
+            ) => self.body(
+                path,
+                (&old_struct.name, &old_struct.version),
+                &old_struct.body,
+                (&new_struct.name, &new_struct.version),
+                &new_struct.body,
+            ),
+            (
+                Type::Union(old_union) | Type::Object(Object::Union(old_union)),
+                Type::Union(new_union) | Type::Object(Object::Union(new_union)),
+            ) => self.union_(path, old_union, new_union),
+            (
+                Type::Enum(old_enum) | Type::Object(Object::Enum(old_enum)),
+                Type::Enum(new_enum) | Type::Object(Object::Enum(new_enum)),
+            ) => self.enum_(path, old_enum, new_enum),
+            (Type::Flags(old_flags), Type::Flags(new_flags)) => {
+                let old_members = resolve(
+                    self.old,
+                    &old_flags.name,
+                    &old_flags.version,
+                    &old_flags.items,
+                );
+                let new_members = resolve(
+                    self.new,
+                    &new_flags.name,
+                    &new_flags.version,
+                    &new_flags.items,
+                );
+                self.members(path, "flag", old_members, new_members, |old, new| {
+                    flag_change(old, new)
+                });
+            }
+            (Type::Function(old_function), Type::Function(new_function)) => {
+                self.struct_items(
+                    path,
+                    (&old_function.name, &old_function.version),
+                    &old_function.items,
+                    (&new_function.name, &new_function.version),
+                    &new_function.items,
+                );
+                let format_return = |return_type: &Option<FieldType>| match return_type {
+                    Some(return_type) => format_field_type(return_type),
+                    None => "()".to_string(),
+                };
+                if old_function.return_type != new_function.return_type {
+                    self.breaking(
+                        path,
+                        format!(
+                            "return type changed from `{}` to `{}`",
+                            format_return(&old_function.return_type),
+                            format_return(&new_function.return_type)
+                        ),
+                    );
+                }
+            }
+            (Type::Command(old_command), Type::Command(new_command)) => self.struct_items(
+                path,
+                (&old_command.name, &old_command.version),
+                &old_command.items,
+                (&new_command.name, &new_command.version),
+                &new_command.items,
+            ),
+            _ => unreachable!("type kinds are equal"),
+        }
+    }
+
+    /// Compares the bodies of a struct or union field. `old_type` and
+    /// `new_type` are the name and version of the types declaring them,
+    /// which include blocks are resolved against.
+    fn body(
+        &mut self,
+        path: &str,
+        old_type: (&str, &'a MajorVersion),
+        old_body: &'a StructBody,
+        new_type: (&str, &'a MajorVersion),
+        new_body: &'a StructBody,
+    ) {
+        match (old_body, new_body) {
+            (StructBody::Items(old_items), StructBody::Items(new_items)) => {
+                self.struct_items(path, old_type, old_items, new_type, new_items)
+            }
+            (StructBody::Tuple(old_tuple), StructBody::Tuple(new_tuple)) => {
+                let old_members = resolve(self.old, old_type.0, old_type.1, &old_tuple.0);
+                let new_members = resolve(self.new, new_type.0, new_type.1, &new_tuple.0);
+                self.tuple_members(path, old_members, new_members);
+            }
+            (StructBody::Unit, StructBody::Unit) => {}
+            _ => self.breaking(
+                path,
+                format!(
+                    "changed from {} to {}",
+                    body_kind(old_body),
+                    body_kind(new_body)
+                ),
+            ),
+        }
+    }
+
+    fn struct_items(
+        &mut self,
+        path: &str,
+        old_type: (&str, &'a MajorVersion),
+        old_items: &'a [StructItem],
+        new_type: (&str, &'a MajorVersion),
+        new_items: &'a [StructItem],
+    ) {
+        let old_members = resolve(self.old, old_type.0, old_type.1, old_items);
+        let new_members = resolve(self.new, new_type.0, new_type.1, new_items);
+        self.members(path, "field", old_members, new_members, |old, new| {
+            field_type_change(&old.field_type, &new.field_type)
+        });
+    }
+
+    fn tuple_members(
+        &mut self,
+        path: &str,
+        old_members: Vec<Resolved<'a, TupleField>>,
+        new_members: Vec<Resolved<'a, TupleField>>,
+    ) {
+        for (index, old_member) in old_members.iter().enumerate() {
+            let field_path = format!("{}.{}", path, index);
+            let Some(new_member) = new_members.get(index) else {
+                self.breaking(&field_path, "tuple field was removed".to_string());
+                continue;
+            };
+            if let Some(message) =
+                field_type_change(&old_member.field.field_type, &new_member.field.field_type)
+            {
+                self.breaking(&field_path, message);
+            }
+            self.directives(&field_path, old_member.field, new_member.field);
+        }
+        for (index, new_member) in new_members.iter().enumerate().skip(old_members.len()) {
+            self.added(
+                &format!("{}.{}", path, index),
+                "tuple field",
+                new_member.field,
+            );
+        }
+    }
+
+    fn union_(&mut self, path: &str, old_union: &'a Union, new_union: &'a Union) {
+        let old_members = resolve(
+            self.old,
+            &old_union.name,
+            &old_union.version,
+            &old_union.items,
+        );
+        let new_members = resolve(
+            self.new,
+            &new_union.name,
+            &new_union.version,
+            &new_union.items,
+        );
+        for old_member in &old_members {
+            let Some(new_member) = new_members
+                .iter()
+                .find(|new_member| new_member.field.name == old_member.field.name)
+            else {
+                continue;
+            };
+            let variant_path = format!("{}.{}", path, old_member.field.name);
+            self.body(
+                &variant_path,
+                (
+                    &old_union.name,
+                    old_member.included_from.unwrap_or(&old_union.version),
+                ),
+                &old_member.field.body,
+                (
+                    &new_union.name,
+                    new_member.included_from.unwrap_or(&new_union.version),
+                ),
+                &new_member.field.body,
+            );
+        }
+        self.members(path, "variant", old_members, new_members, |_, _| None);
+    }
+
+    fn enum_(&mut self, path: &str, old_enum: &'a Enum, new_enum: &'a Enum) {
+        let old_members = resolve(self.old, &old_enum.name, &old_enum.version, &old_enum.items);
+        let new_members = resolve(self.new, &new_enum.name, &new_enum.version, &new_enum.items);
+        self.members(path, "variant", old_members, new_members, |old, new| {
+            enum_value_change(old, new)
+        });
+    }
+
+    /// Compares named members, reporting removed and added members, members
+    /// whose minor version directives changed, and the differences
+    /// `compare` finds between members present in both.
+    fn members<F: Member>(
+        &mut self,
+        path: &str,
+        member_kind: &str,
+        old_members: Vec<Resolved<'a, F>>,
+        new_members: Vec<Resolved<'a, F>>,
+        compare: impl Fn(&F, &F) -> Option<String>,
+    ) {
+        let find = |members: &[Resolved<'a, F>], name: &str| {
+            members
+                .iter()
+                .position(|member| member.field.name() == Some(name))
+        };
+
+        let mut last_index = None;
+        let mut reordered = false;
+        for old_member in &old_members {
+            let name = old_member.field.name().unwrap_or_default();
+            let member_path = format!("{}.{}", path, name);
+            let Some(index) = find(&new_members, name) else {
+                let message = if old_member.field.removed().is_some() {
+                    format!("removed {} was deleted", member_kind)
+                } else {
+                    format!(
+                        "{} was deleted instead of removed in a minor version",
+                        member_kind
+                    )
+                };
+                self.breaking(&member_path, message);
+                continue;
+            };
+            reordered |= last_index.is_some_and(|last_index| index < last_index);
+            last_index = Some(index);
+
+            let new_member = &new_members[index];
+            if let Some(message) = compare(old_member.field, new_member.field) {
+                self.breaking(&member_path, message);
+            }
+            self.directives(&member_path, old_member.field, new_member.field);
+        }
+        if reordered {
+            self.breaking(path, format!("{}s were reordered", member_kind));
+        }
+
+        for new_member in &new_members {
+            let name = new_member.field.name().unwrap_or_default();
+            if find(&old_members, name).is_none() {
+                self.added(&format!("{}.{}", path, name), member_kind, new_member.field);
+            }
+        }
+    }
+
+    fn directives(&mut self, path: &str, old: &impl Member, new: &impl Member) {
+        if old.added() != new.added() {
+            self.breaking(path, "changed its `@add` version".to_string());
+        }
+        match (old.removed(), new.removed()) {
+            (None, Some(removed)) => {
+                self.compatible(path, format!("was removed in {}", minor_version(removed)))
+            }
+            (old_removed, new_removed) if old_removed != new_removed => {
+                self.breaking(path, "changed its `@rem` version".to_string())
+            }
+            _ => {}
+        }
+    }
+
+    fn added(&mut self, path: &str, member_kind: &str, member: &impl Member) {
+        match member.added() {
+            Some(added) => self.compatible(
+                path,
+                format!("{} was added in {}", member_kind, minor_version(added)),
+            ),
+            // Union and enum variants cannot be added in a minor version.
+            None if member_kind == "variant" => self.breaking(
+                path,
+                "variant was added to a published type version".to_string(),
+            ),
+            None => self.breaking(path, format!("{} was added without `@add`", member_kind)),
+        }
+    }
+}
+
+fn type_path(type_def: &Type) -> String {
+    format!("{}@ver({})", type_def.name(), type_def.version().0)
+}
+
+fn kind(type_def: &Type) -> &'static str {
+    match type_def {
+        Type::Object(Object::Struct(_)) => "an object struct",
+        Type::Object(Object::Union(_)) => "an object union",
+        Type::Object(Object::Enum(_)) => "an object enum",
+        Type::Struct(_) => "a struct",
+        Type::Union(_) => "a union",
+        Type::Enum(_) => "an enum",
+        Type::Flags(_) => "flags",
+        Type::Function(_) => "a function",
+        Type::Command(_) => "a command",
+    }
+}
+
+fn body_kind(body: &StructBody) -> &'static str {
+    match body {
+        StructBody::Items(_) => "named fields",
+        StructBody::Tuple(_) => "tuple fields",
+        StructBody::Unit => "a unit",
+    }
+}
+
+fn minor_version(version: &MinorVersion) -> String {
+    format!("{}.{}", version.0, version.1)
+}
+
+fn field_type_change(old: &FieldType, new: &FieldType) -> Option<String> {
+    (old != new).then(|| {
+        format!(
+            "type changed from `{}` to `{}`",
+            format_field_type(old),
+            format_field_type(new)
+        )
+    })
+}
+
+fn enum_value_change(old: &EnumField, new: &EnumField) -> Option<String> {
+    (old.value != new.value).then(|| "value changed".to_string())
+}
+
+fn flag_change(old: &FlagsField, new: &FlagsField) -> Option<String> {
+    (old.bit != new.bit).then(|| format!("bit changed from {} to {}", old.bit, new.bit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn reports_changes() {
+        let old: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            const NAME_LEN: u32 = 8;
+            type Id = u64;
+
+            @ver(1)
+            struct User {
+                id: Id,
+                name: str,
+                age: u8,
+            }
+
+            @ver(1)
+            struct Point (f32, f32)
+
+            @ver(1)
+            union Event {
+                Joined(User@ver(1)),
+                Left,
+            }
+
+            @ver(1)
+            enum Status {
+                Active,
+                Banned,
+            }
+
+            @ver(1)
+            flags Access {
+                Read = 0,
+            }
+
+            @ver(1)
+            fn Rename (
+                name: str,
+            ) -> bool
+        })
+        .unwrap();
+        let new: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            const NAME_LEN: u32 = 16;
+            const MAX_AGE: u8 = 150;
+            type Id = u64;
+
+            @ver(1)
+            struct User {
+                id: Id,
+                name: str,
+                age: u16,
+                @add(1.1)
+                email: ?str,
+            }
+
+            @ver(2)
+            struct User {
+                @ver(1) {
+                    @rem(age),
+                },
+            }
+
+            @ver(1)
+            struct Point (f32, f32, f32)
+
+            @ver(1)
+            union Event {
+                Joined(User@ver(1)),
+                @rem(1.1)
+                Left,
+            }
+
+            @ver(1)
+            enum Status {
+                Banned,
+                Active,
+                Deleted,
+            }
+
+            @ver(1)
+            flags Access {
+                Read = 0,
+                @add(1.1)
+                Write = 1,
+            }
+
+            @ver(1)
+            cmd Rename (
+                name: str,
+            )
+        })
+        .unwrap();
+
+        let changes: Vec<String> = diff(&old, &new)
+            .iter()
+            .map(|change| change.to_string())
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                "breaking: NAME_LEN: constant changed its type or value",
+                "compatible: MAX_AGE: constant was added",
+                "breaking: User@ver(1).age: type changed from `u8` to `u16`",
+                "compatible: User@ver(1).email: field was added in 1.1",
+                "breaking: Point@ver(1).2: tuple field was added without `@add`",
+                "compatible: Event@ver(1).Left: was removed in 1.1",
+                "breaking: Status@ver(1): variants were reordered",
+                "breaking: Status@ver(1).Deleted: variant was added to a published type version",
+                "compatible: Access@ver(1).Write: flag was added in 1.1",
+                "breaking: Rename@ver(1): changed from a function to a command",
+                "compatible: User@ver(2): type version was added",
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+pub mod docs;
+pub mod json_schema;
+pub mod proto;
+pub mod typescript;
+
+use std::collections::HashMap;
+
+use crate::parse::{Scheme, Use};
+
+/// A file produced by an exporter that writes one file per type.
+#[derive(Debug)]
+pub struct Document {
+    /// The path of the document, e.g. `scheme/name/StructV2.json`.
+    pub path: String,
+    pub contents: String,
+}
+
+/// Returns the name used for `version` of the type called `name` in formats
+/// without versioned types, e.g. `StructV2`.
+pub fn version_name(name: &str, version: u16) -> String {
+    format!("{}V{}", name, version)
+}
+
+/// Returns the name of each scheme `scheme` brings into scope, keyed by the
+/// name it is referred to by.
+///
+/// The name is the `sch` name of the scheme `find_scheme` returns for the
+/// `use` item, so that references into a used scheme point at the paths its
+/// own export has. Schemes that are not found are assumed to be named after
+/// their use path, e.g. `a/b` for `use crate::a::b;`.
+pub fn used_scheme_names<'a, 'b>(
+    scheme: &'a Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'b Scheme>,
+) -> HashMap<&'a str, String> {
+    scheme
+        .uses
+        .iter()
+        .map(|use_| {
+            let name = match find_scheme(use_) {
+                Some(used) => used.name.clone(),
+                None => use_
+                    .segments
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|segment| !matches!(*segment, "crate" | "self" | "super"))
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            };
+            (use_.name(), name)
+        })
+        .collect()
+}
+
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev_lowercase = false;
+    for c in name.chars() {
+        if c.is_uppercase() {
+            if prev_lowercase {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+            prev_lowercase = false;
+        } else {
+            snake.push(c);
+            prev_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        }
+    }
+    snake
+}
+
+pub fn to_upper_snake_case(name: &str) -> String {
+    to_snake_case(name).to_uppercase()
+}
+
+pub fn to_pascal_case(name: &str) -> String {
+    let mut pascal = String::new();
+    let mut capitalize = true;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            pascal.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            pascal.push(c);
+        }
+    }
+    pascal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_conversions() {
+        assert_eq!(to_snake_case("NewTypeStruct"), "new_type_struct");
+        assert_eq!(to_snake_case("StructV2"), "struct_v2");
+        assert_eq!(to_snake_case("already_snake"), "already_snake");
+        assert_eq!(to_upper_snake_case("EnumV1"), "ENUM_V1");
+        assert_eq!(to_pascal_case("field_name"), "FieldName");
+        assert_eq!(to_pascal_case("uint32"), "Uint32");
+        assert_eq!(to_pascal_case("Struct"), "Struct");
+    }
+}
@@ -0,0 +1,1028 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    export::{used_scheme_names, Document},
+    format::{format_const_value, primitive_name},
+    parse::{
+        ArraySize, Collection, FieldType, Include, IncludeItem, MajorVersion, MinorVersion, Object,
+        Scheme, StructBody, StructItem, TupleItem, Type, Use,
+    },
+    resolve::{resolve, Item, Member, Resolved},
+};
+
+/// Renders `scheme` as Markdown documentation.
+///
+/// `scheme/name/index.md` lists the scheme's uses, constants, aliases and
+/// types, and every type gets a page, e.g. `scheme/name/User.md`, with a
+/// section per major version. A section lists the members of the version
+/// along with the minor versions they were added or removed in and the
+/// version they were included from, the members its include blocks leave
+/// out, and the changes made in each minor version.
+///
+/// Type references link to the section of the version they name. Links into
+/// a used scheme are relative, to the pages its own documentation has under
+/// its `sch` name. `find_scheme` looks up the scheme a `use` item brings
+/// into scope, see [`used_scheme_names`].
+pub fn markdown<'a>(
+    scheme: &Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'a Scheme>,
+) -> Vec<Document> {
+    pages(scheme, find_scheme, "md")
+        .into_iter()
+        .map(|page| Document {
+            contents: page.markdown(),
+            path: page.path,
+        })
+        .collect()
+}
+
+/// Renders `scheme` as HTML documentation, with the same pages as
+/// [`markdown`].
+pub fn html<'a>(
+    scheme: &Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'a Scheme>,
+) -> Vec<Document> {
+    pages(scheme, find_scheme, "html")
+        .into_iter()
+        .map(|page| Document {
+            contents: page.html(),
+            path: page.path,
+        })
+        .collect()
+}
+
+fn pages<'a>(
+    scheme: &Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'a Scheme>,
+    extension: &'static str,
+) -> Vec<Page> {
+    let docs = Docs {
+        scheme,
+        used_schemes: used_scheme_names(scheme, find_scheme),
+        extension,
+    };
+
+    let mut types: Vec<(&str, Vec<&Type>)> = Vec::new();
+    for type_def in &scheme.types {
+        match types.iter_mut().find(|(name, _)| *name == type_def.name()) {
+            Some((_, versions)) => versions.push(type_def),
+            None => types.push((type_def.name(), vec![type_def])),
+        }
+    }
+    for (_, versions) in &mut types {
+        versions.sort_by_key(|type_def| type_def.version().0);
+    }
+
+    let mut pages = vec![docs.index(&types)];
+    for (name, versions) in &types {
+        pages.push(docs.type_page(name, versions));
+    }
+    pages
+}
+
+struct Page {
+    path: String,
+    title: String,
+    blocks: Vec<Block>,
+}
+
+enum Block {
+    /// A level 2 heading and the id it is linked to by.
+    Heading(String, String),
+    Paragraph(Vec<Inline>),
+    List(Vec<Vec<Inline>>),
+    Table(Vec<&'static str>, Vec<Vec<Vec<Inline>>>),
+}
+
+enum Inline {
+    Text(String),
+    Code(String),
+    /// Link text, shown as code, and the link target.
+    Link(String, String),
+}
+
+fn text(text: &str) -> Inline {
+    Inline::Text(text.to_string())
+}
+
+/// Appends `code` to `out`, merging it with preceding code.
+fn code(out: &mut Vec<Inline>, code: &str) {
+    match out.last_mut() {
+        Some(Inline::Code(last)) => last.push_str(code),
+        _ => out.push(Inline::Code(code.to_string())),
+    }
+}
+
+struct Docs<'a> {
+    scheme: &'a Scheme,
+    used_schemes: HashMap<&'a str, String>,
+    extension: &'static str,
+}
+
+impl<'a> Docs<'a> {
+    fn path(&self, name: &str) -> String {
+        format!("{}/{}.{}", self.scheme.name, name, self.extension)
+    }
+
+    /// Returns the link from a page of this scheme to the page `name` of the
+    /// scheme brought into scope as `alias`, or `None` if no `use` item
+    /// brings a scheme into scope as `alias`.
+    fn used_scheme_link(&self, alias: &str, name: &str) -> Option<String> {
+        let from: Vec<&str> = self.scheme.name.split('/').collect();
+        let to: Vec<&str> = self.used_schemes.get(alias)?.split('/').collect();
+        let common = from
+            .iter()
+            .zip(&to)
+            .take_while(|(from, to)| from == to)
+            .count();
+
+        let mut link = "../".repeat(from.len() - common);
+        for segment in &to[common..] {
+            link.push_str(segment);
+            link.push('/');
+        }
+        link.push_str(&format!("{}.{}", name, self.extension));
+        Some(link)
+    }
+
+    fn index(&self, types: &[(&str, Vec<&Type>)]) -> Page {
+        let mut blocks = Vec::new();
+
+        if !self.scheme.uses.is_empty() {
+            blocks.push(Block::Heading("Uses".to_string(), "uses".to_string()));
+            let uses = self
+                .scheme
+                .uses
+                .iter()
+                .map(|use_| {
+                    let path = use_.segments.join("::");
+                    let mut item = vec![match self.used_scheme_link(use_.name(), "index") {
+                        Some(href) => Inline::Link(path, href),
+                        None => Inline::Code(path),
+                    }];
+                    if let Some(alias) = &use_.alias {
+                        item.push(text(" as "));
+                        item.push(Inline::Code(alias.clone()));
+                    }
+                    item
+                })
+                .collect();
+            blocks.push(Block::List(uses));
+        }
+
+        if !self.scheme.consts.is_empty() {
+            blocks.push(Block::Heading(
+                "Constants".to_string(),
+                "constants".to_string(),
+            ));
+            let rows = self
+                .scheme
+                .consts
+                .iter()
+                .map(|const_| {
+                    vec![
+                        vec![Inline::Code(const_.name.clone())],
+                        vec![Inline::Code(primitive_name(&const_.const_type).to_string())],
+                        vec![Inline::Code(format_const_value(&const_.value))],
+                    ]
+                })
+                .collect();
+            blocks.push(table(vec!["Constant", "Type", "Value"], rows));
+        }
+
+        if !self.scheme.aliases.is_empty() {
+            blocks.push(Block::Heading("Aliases".to_string(), "aliases".to_string()));
+            let rows = self
+                .scheme
+                .aliases
+                .iter()
+                .map(|alias| {
+                    let mut alias_type = Vec::new();
+                    self.field_type(&alias.field_type, &mut alias_type);
+                    vec![vec![Inline::Code(alias.name.clone())], alias_type]
+                })
+                .collect();
+            blocks.push(table(vec!["Alias", "Type"], rows));
+        }
+
+        if !types.is_empty() {
+            blocks.push(Block::Heading("Types".to_string(), "types".to_string()));
+            let rows = types
+                .iter()
+                .map(|(name, versions)| {
+                    let mut version_links = Vec::new();
+                    for (index, type_def) in versions.iter().enumerate() {
+                        if index > 0 {
+                            version_links.push(text(", "));
+                        }
+                        version_links.push(self.version_link(name, type_def.version()));
+                    }
+                    let latest = versions.last().unwrap();
+                    vec![
+                        vec![Inline::Link(
+                            name.to_string(),
+                            format!("{}.{}", name, self.extension),
+                        )],
+                        vec![text(kind(latest))],
+                        version_links,
+                    ]
+                })
+                .collect();
+            blocks.push(table(vec!["Type", "Kind", "Versions"], rows));
+        }
+
+        Page {
+            path: self.path("index"),
+            title: self.scheme.name.clone(),
+            blocks,
+        }
+    }
+
+    fn type_page(&self, name: &str, versions: &[&'a Type]) -> Page {
+        let mut blocks = vec![Block::Paragraph(vec![
+            text("Defined in "),
+            Inline::Link(
+                self.scheme.name.clone(),
+                format!("index.{}#types", self.extension),
+            ),
+            text("."),
+        ])];
+        for type_def in versions {
+            self.version_blocks(type_def, &mut blocks);
+        }
+        Page {
+            path: self.path(name),
+            title: name.to_string(),
+            blocks,
+        }
+    }
+
+    fn version_blocks(&self, type_def: &'a Type, blocks: &mut Vec<Block>) {
+        let name = type_def.name();
+        let version = type_def.version();
+        blocks.push(Block::Heading(
+            format!("Version {}", version.0),
+            version_id(version),
+        ));
+
+        let kind = kind(type_def);
+        // Of the kinds starting with a vowel, only `union` takes "a".
+        let article = if kind.starts_with(['e', 'o']) {
+            "An"
+        } else {
+            "A"
+        };
+        let mut summary = vec![text(&format!("{} {}", article, kind))];
+        for (index, param) in type_def.generics().iter().enumerate() {
+            summary.push(text(if index == 0 {
+                " with generic parameters "
+            } else {
+                ", "
+            }));
+            summary.push(Inline::Code(param.name.clone()));
+        }
+        summary.push(text("."));
+        blocks.push(Block::Paragraph(summary));
+
+        match type_def {
+            Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => match &struct_.body {
+                StructBody::Items(items) => self.struct_fields(name, version, items, blocks),
+                StructBody::Tuple(tuple) => {
+                    blocks.extend(self.includes(&tuple.0));
+                    let members = resolve(self.scheme, name, version, &tuple.0);
+                    let rows = members
+                        .iter()
+                        .enumerate()
+                        .map(|(index, member)| {
+                            let mut field_type = Vec::new();
+                            self.field_type(&member.field.field_type, &mut field_type);
+                            vec![
+                                vec![Inline::Code(index.to_string())],
+                                field_type,
+                                minor_version(member.field.added()),
+                                self.included_from(name, member),
+                            ]
+                        })
+                        .collect();
+                    blocks.push(table(vec!["Field", "Type", "Added", "Included from"], rows));
+                    let labels = (0..members.len()).map(|index| index.to_string());
+                    blocks.extend(changes(&members, labels));
+                }
+                StructBody::Unit => blocks.push(Block::Paragraph(vec![text("It has no fields.")])),
+            },
+            Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+                blocks.extend(self.includes(&union_.items));
+                let members = resolve(self.scheme, name, version, &union_.items);
+                let rows = members
+                    .iter()
+                    .map(|member| {
+                        let version = member.included_from.unwrap_or(version);
+                        vec![
+                            vec![Inline::Code(member.field.name.clone())],
+                            self.variant_fields(name, version, &member.field.body),
+                            minor_version(member.field.removed()),
+                            self.included_from(name, member),
+                        ]
+                    })
+                    .collect();
+                blocks.push(table(
+                    vec!["Variant", "Fields", "Removed", "Included from"],
+                    rows,
+                ));
+                let labels = members.iter().map(|member| member.field.name.clone());
+                blocks.extend(changes(&members, labels));
+            }
+            Type::Enum(enum_) | Type::Object(Object::Enum(enum_)) => {
+                blocks.extend(self.includes(&enum_.items));
+                let members = resolve(self.scheme, name, version, &enum_.items);
+                let rows = members
+                    .iter()
+                    .map(|member| {
+                        let value = match member.field.value {
+                            Some(value) => vec![Inline::Code(value.to_string())],
+                            None => Vec::new(),
+                        };
+                        vec![
+                            vec![Inline::Code(member.field.name.clone())],
+                            value,
+                            minor_version(member.field.removed()),
+                            self.included_from(name, member),
+                        ]
+                    })
+                    .collect();
+                blocks.push(table(
+                    vec!["Variant", "Value", "Removed", "Included from"],
+                    rows,
+                ));
+                let labels = members.iter().map(|member| member.field.name.clone());
+                blocks.extend(changes(&members, labels));
+            }
+            Type::Flags(flags) => {
+                blocks.extend(self.includes(&flags.items));
+                let members = resolve(self.scheme, name, version, &flags.items);
+                let rows = members
+                    .iter()
+                    .map(|member| {
+                        vec![
+                            vec![Inline::Code(member.field.name.clone())],
+                            vec![Inline::Code(member.field.bit.to_string())],
+                            minor_version(member.field.added()),
+                            minor_version(member.field.removed()),
+                            self.included_from(name, member),
+                        ]
+                    })
+                    .collect();
+                blocks.push(table(
+                    vec!["Flag", "Bit", "Added", "Removed", "Included from"],
+                    rows,
+                ));
+                let labels = members.iter().map(|member| member.field.name.clone());
+                blocks.extend(changes(&members, labels));
+            }
+            Type::Function(function) => {
+                self.struct_fields(name, version, &function.items, blocks);
+                let mut returns = vec![text("Returns ")];
+                match &function.return_type {
+                    Some(return_type) => self.field_type(return_type, &mut returns),
+                    None => returns = vec![text("Returns nothing")],
+                }
+                returns.push(text("."));
+                blocks.push(Block::Paragraph(returns));
+            }
+            Type::Command(command) => self.struct_fields(name, version, &command.items, blocks),
+        }
+    }
+
+    fn struct_fields(
+        &self,
+        name: &str,
+        version: &MajorVersion,
+        items: &'a [StructItem],
+        blocks: &mut Vec<Block>,
+    ) {
+        blocks.extend(self.includes(items));
+        let members = resolve(self.scheme, name, version, items);
+        let rows = members
+            .iter()
+            .map(|member| {
+                let mut field_type = Vec::new();
+                self.field_type(&member.field.field_type, &mut field_type);
+                vec![
+                    vec![Inline::Code(member.field.name.clone())],
+                    field_type,
+                    minor_version(member.field.added()),
+                    self.included_from(name, member),
+                ]
+            })
+            .collect();
+        blocks.push(table(vec!["Field", "Type", "Added", "Included from"], rows));
+        let labels = members.iter().map(|member| member.field.name.clone());
+        blocks.extend(changes(&members, labels));
+    }
+
+    /// Describes the include blocks in `items`, one paragraph each.
+    fn includes<I: Item>(&self, items: &[I]) -> Vec<Block> {
+        items
+            .iter()
+            .filter_map(Item::include)
+            .map(|include| {
+                let mut paragraph = vec![
+                    text("Includes the members of "),
+                    Inline::Link(
+                        format!("@ver({})", include.version.0),
+                        format!("#{}", version_id(&include.version)),
+                    ),
+                ];
+                let removed = include_items(include, |item| matches!(item, IncludeItem::Rem(_)));
+                if !removed.is_empty() {
+                    paragraph.push(text(" except "));
+                    paragraph.extend(removed);
+                }
+                let added = include_items(include, |item| matches!(item, IncludeItem::Add(_)));
+                if !added.is_empty() {
+                    paragraph.push(text(", keeping the removed "));
+                    paragraph.extend(added);
+                }
+                paragraph.push(text("."));
+                Block::Paragraph(paragraph)
+            })
+            .collect()
+    }
+
+    fn included_from<F>(&self, name: &str, member: &Resolved<F>) -> Vec<Inline> {
+        match member.included_from {
+            Some(version) => vec![self.version_link(name, version)],
+            None => Vec::new(),
+        }
+    }
+
+    fn version_link(&self, name: &str, version: &MajorVersion) -> Inline {
+        Inline::Link(
+            format!("@ver({})", version.0),
+            format!("{}.{}#{}", name, self.extension, version_id(version)),
+        )
+    }
+
+    /// Returns the fields of a union variant as a struct or tuple body.
+    fn variant_fields(
+        &self,
+        name: &str,
+        version: &MajorVersion,
+        body: &'a StructBody,
+    ) -> Vec<Inline> {
+        let mut out = Vec::new();
+        match body {
+            StructBody::Items(items) => {
+                code(&mut out, "{ ");
+                for (index, member) in resolve(self.scheme, name, version, items)
+                    .iter()
+                    .enumerate()
+                {
+                    if index > 0 {
+                        code(&mut out, ", ");
+                    }
+                    code(&mut out, &format!("{}: ", member.field.name));
+                    self.field_type(&member.field.field_type, &mut out);
+                }
+                code(&mut out, " }");
+            }
+            StructBody::Tuple(tuple) => {
+                code(&mut out, "(");
+                for (index, member) in resolve(self.scheme, name, version, &tuple.0)
+                    .iter()
+                    .enumerate()
+                {
+                    if index > 0 {
+                        code(&mut out, ", ");
+                    }
+                    self.field_type(&member.field.field_type, &mut out);
+                }
+                code(&mut out, ")");
+            }
+            StructBody::Unit => {}
+        }
+        out
+    }
+
+    /// Appends `field_type`, as it is written in schema source, to `out`,
+    /// linking the types it references.
+    fn field_type(&self, field_type: &FieldType, out: &mut Vec<Inline>) {
+        match field_type {
+            FieldType::Primitive(_, primitive) => code(out, primitive_name(primitive)),
+            FieldType::Type(_, name, extern_name, generic_args, version) => {
+                let type_name = match extern_name {
+                    Some(extern_name) => format!("{}::{}", name, extern_name),
+                    None => name.clone(),
+                };
+                match self.type_link(name, extern_name.as_deref(), version.as_ref()) {
+                    Some(href) => out.push(Inline::Link(type_name, href)),
+                    None => code(out, &type_name),
+                }
+                if !generic_args.is_empty() {
+                    code(out, "<");
+                    for (index, arg) in generic_args.iter().enumerate() {
+                        if index > 0 {
+                            code(out, ", ");
+                        }
+                        self.field_type(arg, out);
+                    }
+                    code(out, ">");
+                }
+                if let Some(version) = version {
+                    code(out, &format!("@ver({})", version.0));
+                }
+            }
+            FieldType::Optional(_, inner) => {
+                code(out, "?");
+                self.field_type(inner, out);
+            }
+            FieldType::Reference(_, inner) => {
+                code(out, "&");
+                self.field_type(inner, out);
+            }
+            FieldType::Array(_, inner, size) => {
+                code(out, "[");
+                self.field_type(inner, out);
+                code(out, "; ");
+                match size {
+                    ArraySize::Value(size) => code(out, &size.to_string()),
+                    ArraySize::Const(_, name) => out.push(Inline::Link(
+                        name.clone(),
+                        format!("index.{}#constants", self.extension),
+                    )),
+                }
+                code(out, "]");
+            }
+            FieldType::List(_, inner) => {
+                code(out, "[");
+                self.field_type(inner, out);
+                code(out, "]");
+            }
+            FieldType::Map(_, key_type, value_type, collection) => {
+                code(out, "[");
+                self.field_type(key_type, out);
+                code(out, ": ");
+                self.field_type(value_type, out);
+                code(out, collection_suffix(collection));
+                code(out, "]");
+            }
+            FieldType::Set(_, inner, collection) => {
+                code(out, "{");
+                self.field_type(inner, out);
+                code(out, collection_suffix(collection));
+                code(out, "}");
+            }
+            FieldType::Tuple(_, tuple) => {
+                code(out, "(");
+                let fields = tuple.0.iter().filter_map(|item| match item {
+                    TupleItem::Field(field) => Some(field),
+                    TupleItem::Include(_) => None,
+                });
+                for (index, field) in fields.enumerate() {
+                    if index > 0 {
+                        code(out, ", ");
+                    }
+                    self.field_type(&field.field_type, out);
+                }
+                code(out, ")");
+            }
+        }
+    }
+
+    /// Returns the link to the type a field type names, or `None` for
+    /// generic parameters and types of schemes that are not used.
+    fn type_link(
+        &self,
+        name: &str,
+        extern_name: Option<&str>,
+        version: Option<&MajorVersion>,
+    ) -> Option<String> {
+        let fragment = match version {
+            Some(version) => format!("#{}", version_id(version)),
+            None => String::new(),
+        };
+        if let Some(extern_name) = extern_name {
+            let link = self.used_scheme_link(name, extern_name)?;
+            return Some(format!("{}{}", link, fragment));
+        }
+        if self.scheme.aliases.iter().any(|alias| alias.name == name) {
+            return Some(format!("index.{}#aliases", self.extension));
+        }
+        if self
+            .scheme
+            .types
+            .iter()
+            .any(|type_def| type_def.name() == name)
+        {
+            return Some(format!("{}.{}{}", name, self.extension, fragment));
+        }
+        None
+    }
+}
+
+fn kind(type_def: &Type) -> &'static str {
+    match type_def {
+        Type::Object(Object::Struct(_)) => "object struct",
+        Type::Object(Object::Union(_)) => "object union",
+        Type::Object(Object::Enum(_)) => "object enum",
+        Type::Struct(_) => "struct",
+        Type::Union(_) => "union",
+        Type::Enum(_) => "enum",
+        Type::Flags(_) => "flags type",
+        Type::Function(_) => "function",
+        Type::Command(_) => "command",
+    }
+}
+
+fn version_id(version: &MajorVersion) -> String {
+    format!("version-{}", version.0)
+}
+
+fn minor_version(version: Option<&MinorVersion>) -> Vec<Inline> {
+    match version {
+        Some(MinorVersion(major, minor)) => vec![Inline::Code(format!("{}.{}", major, minor))],
+        None => Vec::new(),
+    }
+}
+
+fn collection_suffix(collection: &Collection) -> &'static str {
+    match collection {
+        Collection::Hashed => "",
+        Collection::Ordered => "; ord",
+    }
+}
+
+/// Returns the names of the include items `filter` accepts, separated by
+/// commas.
+fn include_items(include: &Include, filter: impl Fn(&IncludeItem) -> bool) -> Vec<Inline> {
+    let mut names = Vec::new();
+    for item in include.items.iter().filter(|item| filter(item)) {
+        let (IncludeItem::Add(name) | IncludeItem::Rem(name)) = item;
+        if !names.is_empty() {
+            names.push(text(", "));
+        }
+        names.push(Inline::Code(name.clone()));
+    }
+    names
+}
+
+/// Lists the members a type version added and removed in each of its minor
+/// versions, labelling each member with the matching item of `labels`.
+/// Included members belong to the history of the version they come from.
+fn changes<F: Member>(members: &[Resolved<F>], labels: impl Iterator<Item = String>) -> Vec<Block> {
+    let mut versions: BTreeMap<(u16, u16), (Vec<String>, Vec<String>)> = BTreeMap::new();
+    for (member, label) in members.iter().zip(labels) {
+        if member.included_from.is_some() {
+            continue;
+        }
+        if let Some(MinorVersion(major, minor)) = member.field.added() {
+            versions
+                .entry((*major, *minor))
+                .or_default()
+                .0
+                .push(label.clone());
+        }
+        if let Some(MinorVersion(major, minor)) = member.field.removed() {
+            versions.entry((*major, *minor)).or_default().1.push(label);
+        }
+    }
+    if versions.is_empty() {
+        return Vec::new();
+    }
+
+    let items = versions
+        .into_iter()
+        .map(|((major, minor), (added, removed))| {
+            let mut item = vec![Inline::Code(format!("{}.{}", major, minor)), text(":")];
+            for (verb, labels) in [("added", added), ("removed", removed)] {
+                if labels.is_empty() {
+                    continue;
+                }
+                if item.len() > 2 {
+                    item.push(text(";"));
+                }
+                item.push(text(&format!(" {} ", verb)));
+                for (index, label) in labels.into_iter().enumerate() {
+                    if index > 0 {
+                        item.push(text(", "));
+                    }
+                    item.push(Inline::Code(label));
+                }
+            }
+            item
+        })
+        .collect();
+    vec![
+        Block::Paragraph(vec![text("Changes in minor versions:")]),
+        Block::List(items),
+    ]
+}
+
+/// Returns a table of `rows`, leaving out the columns that are empty in
+/// every row.
+fn table(headers: Vec<&'static str>, rows: Vec<Vec<Vec<Inline>>>) -> Block {
+    if rows.is_empty() {
+        return Block::Paragraph(vec![text("It has no members.")]);
+    }
+
+    let used: Vec<bool> = (0..headers.len())
+        .map(|column| rows.iter().any(|row| !row[column].is_empty()))
+        .collect();
+    let headers = headers
+        .into_iter()
+        .zip(&used)
+        .filter_map(|(header, used)| used.then_some(header))
+        .collect();
+    let rows = rows
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .zip(&used)
+                .filter_map(|(cell, used)| used.then_some(cell))
+                .collect()
+        })
+        .collect();
+    Block::Table(headers, rows)
+}
+
+impl Page {
+    fn markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title);
+        for block in &self.blocks {
+            out.push('\n');
+            match block {
+                Block::Heading(heading, _) => out.push_str(&format!("## {}\n", heading)),
+                Block::Paragraph(inlines) => {
+                    out.push_str(&format!("{}\n", inline_markdown(inlines)))
+                }
+                Block::List(items) => {
+                    for item in items {
+                        out.push_str(&format!("- {}\n", inline_markdown(item)));
+                    }
+                }
+                Block::Table(headers, rows) => {
+                    out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                    out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                    for row in rows {
+                        let cells: Vec<String> = row
+                            .iter()
+                            .map(|cell| inline_markdown(cell).replace('|', "\\|"))
+                            .collect();
+                        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn html(&self) -> String {
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n",
+            escape_html(&self.title),
+            escape_html(&self.title)
+        );
+        for block in &self.blocks {
+            match block {
+                Block::Heading(heading, id) => out.push_str(&format!(
+                    "<h2 id=\"{}\">{}</h2>\n",
+                    id,
+                    escape_html(heading)
+                )),
+                Block::Paragraph(inlines) => {
+                    out.push_str(&format!("<p>{}</p>\n", inline_html(inlines)))
+                }
+                Block::List(items) => {
+                    out.push_str("<ul>\n");
+                    for item in items {
+                        out.push_str(&format!("<li>{}</li>\n", inline_html(item)));
+                    }
+                    out.push_str("</ul>\n");
+                }
+                Block::Table(headers, rows) => {
+                    out.push_str("<table>\n<tr>");
+                    for header in headers {
+                        out.push_str(&format!("<th>{}</th>", header));
+                    }
+                    out.push_str("</tr>\n");
+                    for row in rows {
+                        out.push_str("<tr>");
+                        for cell in row {
+                            out.push_str(&format!("<td>{}</td>", inline_html(cell)));
+                        }
+                        out.push_str("</tr>\n");
+                    }
+                    out.push_str("</table>\n");
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+fn inline_markdown(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => text.clone(),
+            Inline::Code(code) => format!("`{}`", code),
+            Inline::Link(code, href) => format!("[`{}`]({})", code, href),
+        })
+        .collect()
+}
+
+fn inline_html(inlines: &[Inline]) -> String {
+    inlines
+        .iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape_html(text),
+            Inline::Code(code) => format!("<code>{}</code>", escape_html(code)),
+            Inline::Link(code, href) => format!(
+                "<a href=\"{}\"><code>{}</code></a>",
+                escape_html(href),
+                escape_html(code)
+            ),
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn renders_docs() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            const NAME_LEN: u32 = 8;
+            type Id = u64;
+
+            @ver(1)
+            struct User {
+                id: Id,
+                name: [char; NAME_LEN],
+                age: u8,
+                @add(1.1)
+                email: ?str,
+            }
+
+            @ver(2)
+            struct User {
+                @ver(1) {
+                    @rem(age),
+                },
+                tags: {str; ord},
+            }
+
+            @ver(1)
+            union Event {
+                Joined(User@ver(2)),
+                Moved {
+                    to: common::Location@ver(1),
+                },
+                @rem(1.1)
+                Left,
+            }
+        })
+        .unwrap();
+
+        let documents = markdown(&scheme, |_| None);
+        let paths: Vec<&str> = documents
+            .iter()
+            .map(|document| document.path.as_str())
+            .collect();
+        assert_eq!(
+            paths,
+            vec!["my/users/index.md", "my/users/User.md", "my/users/Event.md"]
+        );
+
+        assert_eq!(
+            documents[1].contents,
+            r#"# User
+
+Defined in [`my/users`](index.md#types).
+
+## Version 1
+
+A struct.
+
+| Field | Type | Added |
+| --- | --- | --- |
+| `id` | [`Id`](index.md#aliases) |  |
+| `name` | `[char; `[`NAME_LEN`](index.md#constants)`]` |  |
+| `age` | `u8` |  |
+| `email` | `?str` | `1.1` |
+
+Changes in minor versions:
+
+- `1.1`: added `email`
+
+## Version 2
+
+A struct.
+
+Includes the members of [`@ver(1)`](#version-1) except `age`.
+
+| Field | Type | Added | Included from |
+| --- | --- | --- | --- |
+| `id` | [`Id`](index.md#aliases) |  | [`@ver(1)`](User.md#version-1) |
+| `name` | `[char; `[`NAME_LEN`](index.md#constants)`]` |  | [`@ver(1)`](User.md#version-1) |
+| `email` | `?str` | `1.1` | [`@ver(1)`](User.md#version-1) |
+| `tags` | `{str; ord}` |  |  |
+"#
+        );
+
+        assert_eq!(
+            html(&scheme, |_| None)[2].contents,
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Event</title>
+</head>
+<body>
+<h1>Event</h1>
+<p>Defined in <a href="index.html#types"><code>my/users</code></a>.</p>
+<h2 id="version-1">Version 1</h2>
+<p>A union.</p>
+<table>
+<tr><th>Variant</th><th>Fields</th><th>Removed</th></tr>
+<tr><td><code>Joined</code></td><td><code>(</code><a href="User.html#version-2"><code>User</code></a><code>@ver(2))</code></td><td></td></tr>
+<tr><td><code>Moved</code></td><td><code>{ to: </code><a href="../../common/Location.html#version-1"><code>common::Location</code></a><code>@ver(1) }</code></td><td></td></tr>
+<tr><td><code>Left</code></td><td></td><td><code>1.1</code></td></tr>
+</table>
+<p>Changes in minor versions:</p>
+<ul>
+<li><code>1.1</code>: removed <code>Left</code></li>
+</ul>
+</body>
+</html>
+"#
+        );
+    }
+
+    #[test]
+    fn links_used_schemes_relatively() {
+        let common: Scheme = syn::parse2(quote! {
+            sch "my/shared/common";
+
+            @ver(1)
+            struct Location {
+                lat: f64,
+            }
+        })
+        .unwrap();
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            @ver(1)
+            struct User {
+                home: common::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            markdown(&common, |_| None)[1].path,
+            "my/shared/common/Location.md"
+        );
+
+        let documents = markdown(&scheme, |_| Some(&common));
+        assert!(documents[0]
+            .contents
+            .contains("[`crate::common`](../shared/common/index.md)"));
+        assert!(documents[1]
+            .contents
+            .contains("[`common::Location`](../shared/common/Location.md#version-1)"));
+    }
+
+    #[test]
+    fn does_not_link_types_of_unused_schemes() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            struct User {
+                location: nope::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        let documents = markdown(&scheme, |_| None);
+        assert!(documents[1].contents.contains("`nope::Location@ver(1)`"));
+    }
+}
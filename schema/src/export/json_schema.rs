@@ -0,0 +1,897 @@
+use std::collections::HashMap;
+
+use syn::{Error, Result};
+
+use crate::{
+    export::{to_pascal_case, used_scheme_names, version_name, Document},
+    parse::{
+        ArraySize, ConstValue, Enum, Errors, FieldType, MajorVersion, Object, Primitive, Scheme,
+        StructBody, StructItem, Tuple, TupleItem, Type, TypeAlias, Union, Use,
+    },
+    resolve::{resolve, Member},
+};
+
+/// Exports a JSON Schema document for every type version in `scheme`,
+/// describing the values serde's JSON representation produces for it.
+///
+/// Structs are objects whose fields are required unless they are optional
+/// or were added in a minor version, unknown fields are allowed so newer
+/// minor versions validate. Tuple structs and tuples are arrays using
+/// `prefixItems`, newtype structs are their single field and unit structs
+/// are `null`. Unions are externally tagged `oneOf`s and enums are `enum`s
+/// of their field names. Functions get a `Request` document for their
+/// parameters and a `Response` document for their return value.
+///
+/// Maps are objects when their keys are written as strings and arrays of
+/// `[key, value]` pairs otherwise. Generic types have no document of their
+/// own. Each use of one is added to the `$defs` of the document using it.
+///
+/// Documents of a scheme named `a/b/types` have the `$id`
+/// `/a/b/types/NameV1.json`. Types of the same scheme are referenced by path
+/// relative to the document, and types of a used scheme by the `$id` its own
+/// export gives them. `find_scheme` looks up the scheme a `use` item brings
+/// into scope, see [`used_scheme_names`].
+pub fn export<'a>(
+    scheme: &Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'a Scheme>,
+) -> Result<Vec<Document>> {
+    let used_schemes = used_scheme_names(scheme, find_scheme);
+    let aliases: HashMap<&str, &TypeAlias> = scheme
+        .aliases
+        .iter()
+        .map(|alias| (alias.name.as_str(), alias))
+        .collect();
+
+    let mut documents = Vec::new();
+    let mut errors = Errors::default();
+    for type_def in &scheme.types {
+        if !type_def.generics().is_empty() {
+            continue;
+        }
+
+        let name = version_name(type_def.name(), type_def.version().0);
+        let title = format!("{}@ver({})", type_def.name(), type_def.version().0);
+        let mut exporter = Exporter {
+            scheme,
+            used_schemes: &used_schemes,
+            aliases: &aliases,
+            defs: Vec::new(),
+            def_names: Vec::new(),
+            pending: Vec::new(),
+            errors: Errors::default(),
+        };
+
+        match type_def {
+            Type::Function(function) => {
+                let request = exporter.struct_schema(
+                    &function.name,
+                    &function.version,
+                    &function.items,
+                    &HashMap::new(),
+                );
+                let defs = exporter.finish();
+                documents.push(document(
+                    scheme,
+                    &format!("{}Request", name),
+                    &format!("{} request", title),
+                    request,
+                    defs,
+                ));
+
+                let response = match &function.return_type {
+                    Some(return_type) => exporter.field_schema(return_type, &HashMap::new()),
+                    None => null_schema(),
+                };
+                let defs = exporter.finish();
+                documents.push(document(
+                    scheme,
+                    &format!("{}Response", name),
+                    &format!("{} response", title),
+                    response,
+                    defs,
+                ));
+            }
+            _ => {
+                let schema = exporter.type_schema(type_def, &HashMap::new());
+                let defs = exporter.finish();
+                documents.push(document(scheme, &name, &title, schema, defs));
+            }
+        }
+        errors.check(exporter.errors.finish());
+    }
+    errors.finish()?;
+    Ok(documents)
+}
+
+fn document(
+    scheme: &Scheme,
+    name: &str,
+    title: &str,
+    schema: Json,
+    defs: Vec<(String, Json)>,
+) -> Document {
+    let path = format!("{}/{}.json", scheme.name, name);
+
+    let mut members = vec![
+        (
+            "$schema".to_string(),
+            Json::string("https://json-schema.org/draft/2020-12/schema"),
+        ),
+        ("$id".to_string(), Json::String(format!("/{}", path))),
+        ("title".to_string(), Json::String(title.to_string())),
+    ];
+    match schema {
+        Json::Object(schema) => members.extend(schema),
+        schema => members.push(("allOf".to_string(), Json::Array(vec![schema]))),
+    }
+    if !defs.is_empty() {
+        members.push(("$defs".to_string(), Json::Object(defs)));
+    }
+
+    let mut contents = String::new();
+    Json::Object(members).write(&mut contents, 0);
+    contents.push('\n');
+    Document { path, contents }
+}
+
+type Subst<'a> = HashMap<&'a str, Json>;
+
+struct Exporter<'a> {
+    scheme: &'a Scheme,
+    used_schemes: &'a HashMap<&'a str, String>,
+    aliases: &'a HashMap<&'a str, &'a TypeAlias>,
+    defs: Vec<(String, Json)>,
+    /// The names of the definitions in `defs` and `pending`, with the
+    /// generic type and arguments each was built from.
+    def_names: Vec<(&'a Type, Vec<Json>, String)>,
+    pending: Vec<(&'a Type, String, Subst<'a>)>,
+    errors: Errors,
+}
+
+impl<'a> Exporter<'a> {
+    /// Returns the `$defs` needed by the schemas built so far.
+    fn finish(&mut self) -> Vec<(String, Json)> {
+        while let Some((type_def, name, subst)) = self.pending.pop() {
+            let schema = self.type_schema(type_def, &subst);
+            self.defs.push((name, schema));
+        }
+        self.def_names.clear();
+        std::mem::take(&mut self.defs)
+    }
+
+    fn type_schema(&mut self, type_def: &'a Type, subst: &Subst<'a>) -> Json {
+        match type_def {
+            Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => {
+                self.body_schema(&struct_.name, &struct_.version, &struct_.body, subst)
+            }
+            Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+                self.union_schema(union_, subst)
+            }
+            Type::Enum(enum_) | Type::Object(Object::Enum(enum_)) => self.enum_schema(enum_),
+            Type::Flags(_) => integer_schema(0, u64::MAX as i128),
+            Type::Command(command) => {
+                self.struct_schema(&command.name, &command.version, &command.items, subst)
+            }
+            Type::Function(function) => {
+                self.struct_schema(&function.name, &function.version, &function.items, subst)
+            }
+        }
+    }
+
+    fn body_schema(
+        &mut self,
+        name: &str,
+        version: &MajorVersion,
+        body: &'a StructBody,
+        subst: &Subst<'a>,
+    ) -> Json {
+        match body {
+            StructBody::Items(items) => self.struct_schema(name, version, items, subst),
+            StructBody::Tuple(tuple) => {
+                let members = resolve(self.scheme, name, version, &tuple.0);
+                if let [member] = &members[..] {
+                    return self.field_schema(&member.field.field_type, subst);
+                }
+
+                let required = members
+                    .iter()
+                    .filter(|member| {
+                        member.included_from.is_some() || member.field.added().is_none()
+                    })
+                    .count();
+                let items = members
+                    .iter()
+                    .map(|member| self.field_schema(&member.field.field_type, subst))
+                    .collect();
+                tuple_schema(items, required)
+            }
+            StructBody::Unit => null_schema(),
+        }
+    }
+
+    fn struct_schema(
+        &mut self,
+        name: &str,
+        version: &MajorVersion,
+        items: &'a [StructItem],
+        subst: &Subst<'a>,
+    ) -> Json {
+        let mut properties = Vec::new();
+        let mut required = Vec::new();
+        for member in resolve(self.scheme, name, version, items) {
+            let field = member.field;
+            let is_added = member.included_from.is_none() && field.added().is_some();
+            if !is_added && !matches!(field.field_type, FieldType::Optional(..)) {
+                required.push(Json::String(field.name.clone()));
+            }
+            properties.push((
+                field.name.clone(),
+                self.field_schema(&field.field_type, subst),
+            ));
+        }
+
+        let mut schema = vec![
+            ("type".to_string(), Json::string("object")),
+            ("properties".to_string(), Json::Object(properties)),
+        ];
+        if !required.is_empty() {
+            schema.push(("required".to_string(), Json::Array(required)));
+        }
+        Json::Object(schema)
+    }
+
+    fn union_schema(&mut self, union_: &'a Union, subst: &Subst<'a>) -> Json {
+        let mut variants = Vec::new();
+        for member in resolve(self.scheme, &union_.name, &union_.version, &union_.items) {
+            let variant = member.field;
+            let version = member.included_from.unwrap_or(&union_.version);
+            let mut schema = match &variant.body {
+                StructBody::Unit => vec![("const".to_string(), Json::String(variant.name.clone()))],
+                body => vec![
+                    ("type".to_string(), Json::string("object")),
+                    (
+                        "properties".to_string(),
+                        Json::Object(vec![(
+                            variant.name.clone(),
+                            self.body_schema(&union_.name, version, body, subst),
+                        )]),
+                    ),
+                    (
+                        "required".to_string(),
+                        Json::Array(vec![Json::String(variant.name.clone())]),
+                    ),
+                    ("additionalProperties".to_string(), Json::Bool(false)),
+                ],
+            };
+            if variant.removed().is_some() {
+                schema.push(("deprecated".to_string(), Json::Bool(true)));
+            }
+            variants.push(Json::Object(schema));
+        }
+        Json::Object(vec![("oneOf".to_string(), Json::Array(variants))])
+    }
+
+    fn enum_schema(&mut self, enum_: &'a Enum) -> Json {
+        let names = resolve(self.scheme, &enum_.name, &enum_.version, &enum_.items)
+            .iter()
+            .map(|member| Json::String(member.field.name.clone()))
+            .collect();
+        Json::Object(vec![("enum".to_string(), Json::Array(names))])
+    }
+
+    fn field_schema(&mut self, field_type: &'a FieldType, subst: &Subst<'a>) -> Json {
+        match field_type {
+            FieldType::Primitive(_, primitive) => primitive_schema(primitive),
+            FieldType::Type(_, name, None, generic_args, version) => {
+                if version.is_none() && generic_args.is_empty() {
+                    if let Some(schema) = subst.get(name.as_str()) {
+                        return schema.clone();
+                    }
+                    if let Some(alias) = self.aliases.get(name.as_str()) {
+                        return self.field_schema(&alias.field_type, &HashMap::new());
+                    }
+                }
+                self.local_type_schema(name, generic_args, version.as_ref(), subst)
+            }
+            FieldType::Type(span, scheme_alias, Some(name), _, version) => {
+                let name = match version {
+                    Some(version) => version_name(name, version.0),
+                    None => name.clone(),
+                };
+                let Some(scheme_name) = self.used_schemes.get(scheme_alias.as_str()) else {
+                    self.errors.push(Error::new(
+                        *span,
+                        format!("`{}` is not a used scheme", scheme_alias),
+                    ));
+                    return null_schema();
+                };
+                ref_schema(format!("/{}/{}.json", scheme_name, name))
+            }
+            FieldType::Optional(_, inner) => Json::Object(vec![(
+                "anyOf".to_string(),
+                Json::Array(vec![self.field_schema(inner, subst), null_schema()]),
+            )]),
+            FieldType::Reference(..) => integer_schema(0, u64::MAX as i128),
+            FieldType::Array(_, inner, size) => {
+                let size = match size {
+                    ArraySize::Value(size) => Some(*size as i128),
+                    ArraySize::Const(_, name) => self
+                        .scheme
+                        .consts
+                        .iter()
+                        .find(|const_| &const_.name == name)
+                        .and_then(|const_| match const_.value {
+                            ConstValue::Int(size) => Some(size),
+                            _ => None,
+                        }),
+                };
+                let mut schema = vec![
+                    ("type".to_string(), Json::string("array")),
+                    ("items".to_string(), self.field_schema(inner, subst)),
+                ];
+                if let Some(size) = size {
+                    schema.push(("minItems".to_string(), Json::Int(size)));
+                    schema.push(("maxItems".to_string(), Json::Int(size)));
+                }
+                Json::Object(schema)
+            }
+            FieldType::List(_, inner) => Json::Object(vec![
+                ("type".to_string(), Json::string("array")),
+                ("items".to_string(), self.field_schema(inner, subst)),
+            ]),
+            FieldType::Set(_, inner, _) => Json::Object(vec![
+                ("type".to_string(), Json::string("array")),
+                ("items".to_string(), self.field_schema(inner, subst)),
+                ("uniqueItems".to_string(), Json::Bool(true)),
+            ]),
+            FieldType::Map(_, key_type, value_type, _) => {
+                let value = self.field_schema(value_type, subst);
+                match self.key_names(key_type) {
+                    Some(names) => Json::Object(vec![
+                        ("type".to_string(), Json::string("object")),
+                        ("propertyNames".to_string(), names),
+                        ("additionalProperties".to_string(), value),
+                    ]),
+                    None => {
+                        let key = self.field_schema(key_type, subst);
+                        Json::Object(vec![
+                            ("type".to_string(), Json::string("array")),
+                            ("items".to_string(), tuple_schema(vec![key, value], 2)),
+                        ])
+                    }
+                }
+            }
+            FieldType::Tuple(_, Tuple(items)) => {
+                let items: Vec<Json> = items
+                    .iter()
+                    .filter_map(|item| match item {
+                        TupleItem::Field(field) => {
+                            Some(self.field_schema(&field.field_type, subst))
+                        }
+                        TupleItem::Include(_) => None,
+                    })
+                    .collect();
+                let required = items.len();
+                tuple_schema(items, required)
+            }
+        }
+    }
+
+    /// Returns the schema of the object property names serde writes keys of
+    /// `key_type` as, or `None` if it cannot write them as property names.
+    fn key_names(&self, key_type: &FieldType) -> Option<Json> {
+        match key_type {
+            FieldType::Primitive(_, primitive) if primitive.int_range().is_some() => Some(
+                Json::Object(vec![("pattern".to_string(), Json::string("^-?[0-9]+$"))]),
+            ),
+            FieldType::Primitive(
+                _,
+                primitive @ (Primitive::Char
+                | Primitive::String
+                | Primitive::Timestamp
+                | Primitive::Duration
+                | Primitive::Uuid
+                | Primitive::Decimal),
+            ) => Some(primitive_schema(primitive)),
+            FieldType::Type(_, name, None, generic_args, None) if generic_args.is_empty() => {
+                let alias = self.aliases.get(name.as_str())?;
+                self.key_names(&alias.field_type)
+            }
+            FieldType::Type(_, name, None, _, Some(version)) => {
+                let is_enum = self.scheme.types.iter().any(|type_def| {
+                    type_def.name() == name
+                        && type_def.version() == version
+                        && matches!(type_def, Type::Enum(_) | Type::Object(Object::Enum(_)))
+                });
+                is_enum.then(|| ref_schema(format!("{}.json", version_name(name, version.0))))
+            }
+            _ => None,
+        }
+    }
+
+    fn local_type_schema(
+        &mut self,
+        name: &str,
+        generic_args: &'a [FieldType],
+        version: Option<&MajorVersion>,
+        subst: &Subst<'a>,
+    ) -> Json {
+        let Some(version) = version else {
+            return ref_schema(format!("{}.json", name));
+        };
+
+        let mut def_name = version_name(name, version.0);
+        let generic_type = self.scheme.types.iter().find(|type_def| {
+            type_def.name() == name
+                && type_def.version() == version
+                && !type_def.generics().is_empty()
+        });
+        let Some(type_def) = generic_type else {
+            return ref_schema(format!("{}.json", def_name));
+        };
+
+        let args: Vec<Json> = generic_args
+            .iter()
+            .map(|arg| {
+                def_name.push_str(&arg_name(arg));
+                self.field_schema(arg, subst)
+            })
+            .collect();
+        let existing = self
+            .def_names
+            .iter()
+            .find(|(def_type, def_args, _)| std::ptr::eq(*def_type, type_def) && *def_args == args);
+        if let Some((_, _, existing)) = existing {
+            return ref_schema(format!("#/$defs/{}", existing));
+        }
+
+        // Different arguments can still get the same name, e.g. a generic
+        // parameter passed on to another generic type, so later ones are
+        // numbered.
+        let mut unique_name = def_name.clone();
+        let mut number = 1;
+        while self
+            .def_names
+            .iter()
+            .any(|(_, _, name)| *name == unique_name)
+        {
+            number += 1;
+            unique_name = format!("{}{}", def_name, number);
+        }
+        self.def_names
+            .push((type_def, args.clone(), unique_name.clone()));
+        let subst = type_def
+            .generics()
+            .iter()
+            .map(|param| param.name.as_str())
+            .zip(args)
+            .collect();
+        self.pending.push((type_def, unique_name.clone(), subst));
+        ref_schema(format!("#/$defs/{}", unique_name))
+    }
+}
+
+/// Returns a name for `field_type` to use in the names of generic type
+/// definitions.
+fn arg_name(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Primitive(_, primitive) => format!("{:?}", primitive),
+        FieldType::Type(_, name, extern_name, generic_args, version) => {
+            let mut arg = to_pascal_case(name);
+            if let Some(extern_name) = extern_name {
+                arg.push_str(extern_name);
+            }
+            if let Some(version) = version {
+                arg.push_str(&format!("V{}", version.0));
+            }
+            for generic_arg in generic_args {
+                arg.push_str(&arg_name(generic_arg));
+            }
+            arg
+        }
+        FieldType::Optional(_, inner) => format!("{}Value", arg_name(inner)),
+        FieldType::Reference(_, inner) => format!("{}Ref", arg_name(inner)),
+        FieldType::Array(_, inner, ArraySize::Value(size)) => {
+            format!("{}Array{}", arg_name(inner), size)
+        }
+        FieldType::Array(_, inner, ArraySize::Const(_, name)) => {
+            format!(
+                "{}Array{}",
+                arg_name(inner),
+                to_pascal_case(&name.to_lowercase())
+            )
+        }
+        FieldType::List(_, inner) => format!("{}List", arg_name(inner)),
+        FieldType::Set(_, inner, _) => format!("{}Set", arg_name(inner)),
+        FieldType::Map(_, key_type, value_type, _) => {
+            format!("{}{}Map", arg_name(key_type), arg_name(value_type))
+        }
+        FieldType::Tuple(_, Tuple(items)) => {
+            let mut arg = String::new();
+            for item in items {
+                if let TupleItem::Field(field) = item {
+                    arg.push_str(&arg_name(&field.field_type));
+                }
+            }
+            arg.push_str("Tuple");
+            arg
+        }
+    }
+}
+
+fn primitive_schema(primitive: &Primitive) -> Json {
+    if let Some((min, max)) = primitive.int_range() {
+        let max = match primitive {
+            Primitive::UInt128 => None,
+            _ => Some(max),
+        };
+        let mut schema = vec![
+            ("type".to_string(), Json::string("integer")),
+            ("minimum".to_string(), Json::Int(min)),
+        ];
+        schema.extend(max.map(|max| ("maximum".to_string(), Json::Int(max))));
+        return Json::Object(schema);
+    }
+
+    let string = |format: Option<&str>| {
+        let mut schema = vec![("type".to_string(), Json::string("string"))];
+        schema.extend(format.map(|format| ("format".to_string(), Json::string(format))));
+        Json::Object(schema)
+    };
+    match primitive {
+        Primitive::Float32 | Primitive::Float64 => {
+            Json::Object(vec![("type".to_string(), Json::string("number"))])
+        }
+        Primitive::Boolean => Json::Object(vec![("type".to_string(), Json::string("boolean"))]),
+        Primitive::Char => Json::Object(vec![
+            ("type".to_string(), Json::string("string")),
+            ("minLength".to_string(), Json::Int(1)),
+            ("maxLength".to_string(), Json::Int(1)),
+        ]),
+        Primitive::String => string(None),
+        Primitive::Bytes => Json::Object(vec![
+            ("type".to_string(), Json::string("array")),
+            ("items".to_string(), primitive_schema(&Primitive::UInt8)),
+        ]),
+        Primitive::Timestamp => string(Some("date-time")),
+        Primitive::Duration => string(Some("duration")),
+        Primitive::Uuid => string(Some("uuid")),
+        Primitive::Decimal => Json::Object(vec![
+            ("type".to_string(), Json::string("string")),
+            (
+                "pattern".to_string(),
+                Json::string("^-?[0-9]+(\\.[0-9]+)?$"),
+            ),
+        ]),
+        Primitive::Any => Json::Object(Vec::new()),
+        _ => unreachable!("integer primitives are handled above"),
+    }
+}
+
+fn integer_schema(min: i128, max: i128) -> Json {
+    Json::Object(vec![
+        ("type".to_string(), Json::string("integer")),
+        ("minimum".to_string(), Json::Int(min)),
+        ("maximum".to_string(), Json::Int(max)),
+    ])
+}
+
+fn null_schema() -> Json {
+    Json::Object(vec![("type".to_string(), Json::string("null"))])
+}
+
+fn ref_schema(reference: String) -> Json {
+    Json::Object(vec![("$ref".to_string(), Json::String(reference))])
+}
+
+/// Returns the schema of an array holding `items` in order, of which the
+/// first `required` must be present.
+fn tuple_schema(items: Vec<Json>, required: usize) -> Json {
+    let len = items.len();
+    Json::Object(vec![
+        ("type".to_string(), Json::string("array")),
+        ("prefixItems".to_string(), Json::Array(items)),
+        ("items".to_string(), Json::Bool(false)),
+        ("minItems".to_string(), Json::Int(required as i128)),
+        ("maxItems".to_string(), Json::Int(len as i128)),
+    ])
+}
+
+#[derive(Clone, PartialEq)]
+enum Json {
+    Bool(bool),
+    Int(i128),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn string(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Self::Array(_) | Self::Object(_))
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Self::Bool(value) => out.push_str(&value.to_string()),
+            Self::Int(value) => out.push_str(&value.to_string()),
+            Self::String(value) => write_string(out, value),
+            Self::Array(items) if items.iter().all(Self::is_scalar) => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push_str(", ");
+                    }
+                    item.write(out, indent);
+                }
+                out.push(']');
+            }
+            Self::Array(items) => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    out.push_str(if index > 0 { ",\n" } else { "\n" });
+                    out.push_str(&"  ".repeat(indent + 1));
+                    item.write(out, indent + 1);
+                }
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent));
+                out.push(']');
+            }
+            Self::Object(members) if members.is_empty() => out.push_str("{}"),
+            Self::Object(members) => {
+                out.push('{');
+                for (index, (key, value)) in members.iter().enumerate() {
+                    out.push_str(if index > 0 { ",\n" } else { "\n" });
+                    out.push_str(&"  ".repeat(indent + 1));
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                }
+                out.push('\n');
+                out.push_str(&"  ".repeat(indent));
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn write_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn exports_json_schema() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            const NAME_LEN: u32 = 8;
+            type Id = u64;
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+                next: ?Id,
+            }
+
+            @ver(1)
+            struct User {
+                id: Id,
+                name: [char; NAME_LEN],
+                @add(1.1)
+                email: ?str,
+                scores: [Status@ver(1): f64],
+                pair: (u8, str),
+                location: common::Location@ver(1),
+            }
+
+            @ver(1)
+            union Event {
+                Joined(User@ver(1)),
+                @rem(1.1)
+                Left,
+            }
+
+            @ver(1)
+            enum Status {
+                Active,
+                Banned,
+            }
+
+            @ver(1)
+            fn ListUsers (
+                offset: u32,
+            ) -> Page<User@ver(1)>@ver(1)
+        })
+        .unwrap();
+
+        let documents = export(&scheme, |_| None).unwrap();
+        assert_eq!(
+            documents
+                .iter()
+                .map(|document| document.path.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "my/users/UserV1.json",
+                "my/users/EventV1.json",
+                "my/users/StatusV1.json",
+                "my/users/ListUsersV1Request.json",
+                "my/users/ListUsersV1Response.json",
+            ]
+        );
+        assert_eq!(
+            documents[1].contents,
+            r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$id": "/my/users/EventV1.json",
+  "title": "Event@ver(1)",
+  "oneOf": [
+    {
+      "type": "object",
+      "properties": {
+        "Joined": {
+          "$ref": "UserV1.json"
+        }
+      },
+      "required": ["Joined"],
+      "additionalProperties": false
+    },
+    {
+      "const": "Left",
+      "deprecated": true
+    }
+  ]
+}
+"##
+        );
+        assert_eq!(
+            documents[4].contents,
+            r##"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "$id": "/my/users/ListUsersV1Response.json",
+  "title": "ListUsers@ver(1) response",
+  "$ref": "#/$defs/PageV1UserV1",
+  "$defs": {
+    "PageV1UserV1": {
+      "type": "object",
+      "properties": {
+        "items": {
+          "type": "array",
+          "items": {
+            "$ref": "UserV1.json"
+          }
+        },
+        "next": {
+          "anyOf": [
+            {
+              "type": "integer",
+              "minimum": 0,
+              "maximum": 18446744073709551615
+            },
+            {
+              "type": "null"
+            }
+          ]
+        }
+      },
+      "required": ["items"]
+    }
+  }
+}
+"##
+        );
+    }
+
+    #[test]
+    fn names_generic_definitions_distinctly() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            struct Item<T> {
+                value: T,
+            }
+
+            @ver(1)
+            struct Page<T> {
+                item: Item<T>@ver(1),
+            }
+
+            @ver(1)
+            struct User {
+                a: Item<[u8; 4]>@ver(1),
+                b: Item<{u8}>@ver(1),
+                c: Item<[u8]>@ver(1),
+                d: Page<u8>@ver(1),
+                e: Page<str>@ver(1),
+            }
+        })
+        .unwrap();
+
+        let documents = export(&scheme, |_| None).unwrap();
+        let user = &documents[0].contents;
+        for name in [
+            "ItemV1UInt8Array4",
+            "ItemV1UInt8Set",
+            "ItemV1UInt8List",
+            "PageV1UInt8",
+            "PageV1String",
+            "ItemV1T",
+            "ItemV1T2",
+        ] {
+            assert!(user.contains(&format!("\"#/$defs/{}\"", name)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn references_used_schemes_by_id() {
+        let common: Scheme = syn::parse2(quote! {
+            sch "shared/common";
+
+            @ver(1)
+            struct Location {
+                lat: f64,
+                lon: f64,
+            }
+        })
+        .unwrap();
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            @ver(1)
+            struct User {
+                location: common::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        let location = &export(&common, |_| None).unwrap()[0];
+        assert_eq!(location.path, "shared/common/LocationV1.json");
+        assert!(location
+            .contents
+            .contains(r#""$id": "/shared/common/LocationV1.json""#));
+
+        let user = &export(&scheme, |_| Some(&common)).unwrap()[0];
+        assert!(user
+            .contents
+            .contains(r#""$ref": "/shared/common/LocationV1.json""#));
+    }
+
+    #[test]
+    fn rejects_unused_schemes() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            struct User {
+                location: nope::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        let err = export(&scheme, |_| None).unwrap_err();
+        assert_eq!(err.to_string(), "`nope` is not a used scheme");
+    }
+}
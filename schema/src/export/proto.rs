@@ -0,0 +1,838 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use syn::{Error, Result};
+
+use crate::{
+    export::{to_pascal_case, to_snake_case, to_upper_snake_case, used_scheme_names, version_name},
+    parse::{
+        Enum, Errors, FieldType, Flags, MajorVersion, Object, Primitive, Scheme, StructBody,
+        StructItem, Tuple, TupleItem, Type, TypeAlias, Union, Use,
+    },
+    resolve::{resolve, Member, Resolved},
+};
+
+/// Exports `scheme` as a proto3 file.
+///
+/// Every type version becomes a message (or enum) named after the type and
+/// its major version, e.g. `StructV2`. Fields are numbered in declaration
+/// order, with fields added in minor versions numbered after the ones they
+/// were added to, so numbers are stable across minor versions. Members
+/// removed in a minor version are kept and marked deprecated.
+///
+/// Unions become a message with a `oneof` over one nested message per
+/// variant, functions become `rpc`s of a service named after the scheme,
+/// flags and object references are encoded as `uint64`. Generic types are
+/// emitted once per set of generic arguments they are used with, and nested
+/// lists, maps and optionals that proto3 cannot express directly are wrapped
+/// in messages. Enums start with their zero value, adding an `_UNSPECIFIED`
+/// value when they have none, and values above `i32::MAX` are errors.
+///
+/// A scheme named `a/b/types` is exported to `a/b/types.proto` with the
+/// package `a.b.types`, and a used scheme is imported from the file named
+/// after it in the same way. `find_scheme` looks up the scheme a `use` item
+/// brings into scope, see [`used_scheme_names`].
+pub fn export<'a>(
+    scheme: &Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'a Scheme>,
+) -> Result<String> {
+    let mut exporter = Exporter {
+        scheme,
+        used_schemes: used_scheme_names(scheme, find_scheme),
+        aliases: scheme
+            .aliases
+            .iter()
+            .map(|alias| (alias.name.as_str(), alias))
+            .collect(),
+        imports: BTreeSet::new(),
+        synthesized: HashSet::new(),
+        synthesized_blocks: Vec::new(),
+        pending: Vec::new(),
+        errors: Errors::default(),
+    };
+
+    let mut blocks = Vec::new();
+    let mut rpcs = Vec::new();
+    for type_def in &scheme.types {
+        if !type_def.generics().is_empty() {
+            continue;
+        }
+
+        let name = version_name(type_def.name(), type_def.version().0);
+        match type_def {
+            Type::Function(function) => {
+                let request = format!("{}Request", name);
+                let response = format!("{}Response", name);
+                let fields = exporter.struct_fields(
+                    type_def.name(),
+                    type_def.version(),
+                    &function.items,
+                    &HashMap::new(),
+                );
+                blocks.push(exporter.message(&request, fields));
+
+                let mut fields = Vec::new();
+                if let Some(return_type) = &function.return_type {
+                    let proto_type = exporter.map_type(return_type, &HashMap::new());
+                    fields.push(field_decl(&proto_type, "value", 1, false));
+                }
+                blocks.push(exporter.message(&response, fields));
+
+                rpcs.push(format!(
+                    "  rpc {}({}) returns ({});",
+                    name, request, response
+                ));
+            }
+            Type::Flags(_) => {}
+            _ => {
+                if let Some(block) = exporter.type_block(type_def, &name, &HashMap::new()) {
+                    blocks.push(block);
+                }
+            }
+        }
+    }
+
+    while let Some((type_def, name, subst)) = exporter.pending.pop() {
+        if let Some(block) = exporter.type_block(type_def, &name, &subst) {
+            exporter.synthesized_blocks.push(block);
+        }
+    }
+
+    let mut proto = String::from("syntax = \"proto3\";\n\n");
+    proto.push_str(&format!("package {};\n", package_name(&scheme.name)));
+    if !exporter.imports.is_empty() {
+        proto.push('\n');
+        for import in &exporter.imports {
+            proto.push_str(&format!("import \"{}\";\n", import));
+        }
+    }
+    for block in blocks.iter().chain(&exporter.synthesized_blocks) {
+        proto.push('\n');
+        proto.push_str(block);
+    }
+    if !rpcs.is_empty() {
+        let service = scheme.name.rsplit('/').next().unwrap_or(&scheme.name);
+        proto.push_str(&format!(
+            "\nservice {}Service {{\n{}\n}}\n",
+            to_pascal_case(&sanitize(service)),
+            rpcs.join("\n")
+        ));
+    }
+    exporter.errors.finish()?;
+    Ok(proto)
+}
+
+fn package_name(scheme_name: &str) -> String {
+    scheme_name
+        .split('/')
+        .map(sanitize)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn sanitize(segment: &str) -> String {
+    segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+enum Label {
+    None,
+    Optional,
+    Repeated,
+    Map(String),
+}
+
+#[derive(Clone)]
+struct ProtoType {
+    label: Label,
+    name: String,
+}
+
+impl ProtoType {
+    fn plain(name: impl Into<String>) -> Self {
+        Self {
+            label: Label::None,
+            name: name.into(),
+        }
+    }
+
+    /// Returns a name describing this type, used to name the messages
+    /// synthesized for it.
+    fn describe(&self) -> String {
+        let name = to_pascal_case(self.name.rsplit('.').next().unwrap());
+        match &self.label {
+            Label::None => name,
+            Label::Optional => format!("{}Value", name),
+            Label::Repeated => format!("{}List", name),
+            Label::Map(key) => format!("{}{}Map", to_pascal_case(key), name),
+        }
+    }
+}
+
+type Subst<'a> = HashMap<&'a str, ProtoType>;
+
+struct Exporter<'a> {
+    scheme: &'a Scheme,
+    used_schemes: HashMap<&'a str, String>,
+    aliases: HashMap<&'a str, &'a TypeAlias>,
+    imports: BTreeSet<String>,
+    synthesized: HashSet<String>,
+    synthesized_blocks: Vec<String>,
+    pending: Vec<(&'a Type, String, Subst<'a>)>,
+    errors: Errors,
+}
+
+impl<'a> Exporter<'a> {
+    fn type_block(&mut self, type_def: &'a Type, name: &str, subst: &Subst<'a>) -> Option<String> {
+        match type_def {
+            Type::Struct(_) | Type::Object(Object::Struct(_)) => {
+                let body = match type_def {
+                    Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => &struct_.body,
+                    _ => unreachable!(),
+                };
+                let fields = match body {
+                    StructBody::Items(items) => {
+                        self.struct_fields(type_def.name(), type_def.version(), items, subst)
+                    }
+                    StructBody::Tuple(tuple) => {
+                        self.tuple_fields(type_def.name(), type_def.version(), tuple, subst)
+                    }
+                    StructBody::Unit => Vec::new(),
+                };
+                Some(self.message(name, fields))
+            }
+            Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+                Some(self.union_message(union_, name, subst))
+            }
+            Type::Enum(enum_) | Type::Object(Object::Enum(enum_)) => {
+                Some(self.enum_block(enum_, name))
+            }
+            Type::Command(command) => {
+                let fields =
+                    self.struct_fields(type_def.name(), type_def.version(), &command.items, subst);
+                Some(self.message(name, fields))
+            }
+            Type::Flags(Flags { .. }) | Type::Function(_) => None,
+        }
+    }
+
+    fn message(&self, name: &str, fields: Vec<String>) -> String {
+        let mut block = format!("message {} {{\n", name);
+        for field in fields {
+            block.push_str(&format!("  {}\n", field));
+        }
+        block.push_str("}\n");
+        block
+    }
+
+    fn struct_fields(
+        &mut self,
+        name: &str,
+        version: &MajorVersion,
+        items: &'a [StructItem],
+        subst: &Subst<'a>,
+    ) -> Vec<String> {
+        let members = resolve(self.scheme, name, version, items);
+        let fields: Vec<(String, &FieldType)> = members
+            .iter()
+            .map(|member| (to_snake_case(&member.field.name), &member.field.field_type))
+            .collect();
+        self.numbered_fields(&members, fields, subst)
+    }
+
+    fn tuple_fields(
+        &mut self,
+        name: &str,
+        version: &MajorVersion,
+        tuple: &'a Tuple,
+        subst: &Subst<'a>,
+    ) -> Vec<String> {
+        let members = resolve(self.scheme, name, version, &tuple.0);
+        let fields: Vec<(String, &FieldType)> = members
+            .iter()
+            .enumerate()
+            .map(|(index, member)| (format!("field_{}", index), &member.field.field_type))
+            .collect();
+        self.numbered_fields(&members, fields, subst)
+    }
+
+    /// Numbers fields in declaration order, placing fields added in a minor
+    /// version after the fields declared before that minor version.
+    fn numbered_fields<F: Member>(
+        &mut self,
+        members: &[Resolved<'a, F>],
+        fields: Vec<(String, &'a FieldType)>,
+        subst: &Subst<'a>,
+    ) -> Vec<String> {
+        let mut order: Vec<usize> = (0..members.len()).collect();
+        order.sort_by_key(|&index| {
+            let member = &members[index];
+            match (member.included_from, member.field.added()) {
+                (None, Some(minor)) => minor.1,
+                _ => 0,
+            }
+        });
+
+        let mut numbers = vec![0; members.len()];
+        for (number, index) in order.into_iter().enumerate() {
+            numbers[index] = number + 1;
+        }
+
+        fields
+            .into_iter()
+            .zip(numbers)
+            .map(|((name, field_type), number)| {
+                let proto_type = self.map_type(field_type, subst);
+                field_decl(&proto_type, &name, number, false)
+            })
+            .collect()
+    }
+
+    fn union_message(&mut self, union_: &'a Union, name: &str, subst: &Subst<'a>) -> String {
+        let members = resolve(self.scheme, &union_.name, &union_.version, &union_.items);
+
+        let mut block = format!("message {} {{\n", name);
+        let mut oneof = String::from("  oneof value {\n");
+        for (index, member) in members.iter().enumerate() {
+            let variant = &member.field;
+            let version = member.included_from.unwrap_or(&union_.version);
+            let fields = match &variant.body {
+                StructBody::Items(items) => self.struct_fields(&union_.name, version, items, subst),
+                StructBody::Tuple(tuple) => self.tuple_fields(&union_.name, version, tuple, subst),
+                StructBody::Unit => Vec::new(),
+            };
+            for line in self.message(&variant.name, fields).lines() {
+                block.push_str(&format!("  {}\n", line));
+            }
+
+            oneof.push_str(&format!(
+                "    {};\n",
+                field_decl(
+                    &ProtoType::plain(variant.name.clone()),
+                    &to_snake_case(&variant.name),
+                    index + 1,
+                    variant.version.is_some(),
+                )
+                .trim_end_matches(';')
+            ));
+        }
+        oneof.push_str("  }\n");
+        block.push_str(&oneof);
+        block.push_str("}\n");
+        block
+    }
+
+    fn enum_block(&mut self, enum_: &'a Enum, name: &str) -> String {
+        let prefix = to_upper_snake_case(name);
+        let members = resolve(self.scheme, &enum_.name, &enum_.version, &enum_.items);
+
+        let mut values = Vec::new();
+        let mut next_value = 0;
+        for member in &members {
+            let value = member.field.value.map_or(next_value, u64::from);
+            next_value = value + 1;
+            if value > i32::MAX as u64 {
+                self.errors.push(Error::new(
+                    member.field.name_span,
+                    format!(
+                        "`{}` has the value {}, but proto3 enum values must be at most {}",
+                        member.field.name,
+                        value,
+                        i32::MAX
+                    ),
+                ));
+            }
+            values.push((
+                format!("{}_{}", prefix, to_upper_snake_case(&member.field.name)),
+                value,
+                member.field.version.is_some(),
+            ));
+        }
+
+        // proto3 requires the first value to be zero.
+        let mut block = format!("enum {} {{\n", name);
+        match values.iter().position(|(_, value, _)| *value == 0) {
+            Some(index) => {
+                let zero = values.remove(index);
+                values.insert(0, zero);
+            }
+            None => block.push_str(&format!("  {}_UNSPECIFIED = 0;\n", prefix)),
+        }
+        for (value_name, value, deprecated) in values {
+            block.push_str(&format!(
+                "  {} = {}{};\n",
+                value_name,
+                value,
+                if deprecated {
+                    " [deprecated = true]"
+                } else {
+                    ""
+                }
+            ));
+        }
+        block.push_str("}\n");
+        block
+    }
+
+    fn map_type(&mut self, field_type: &'a FieldType, subst: &Subst<'a>) -> ProtoType {
+        match field_type {
+            FieldType::Primitive(_, primitive) => ProtoType::plain(self.primitive(primitive)),
+            FieldType::Type(_, name, None, generic_args, version) => {
+                if version.is_none() && generic_args.is_empty() {
+                    if let Some(proto_type) = subst.get(name.as_str()) {
+                        return proto_type.clone();
+                    }
+                    if let Some(alias) = self.aliases.get(name.as_str()) {
+                        return self.map_type(&alias.field_type, &HashMap::new());
+                    }
+                }
+                self.local_type(name, generic_args, version.as_ref(), subst)
+            }
+            FieldType::Type(span, scheme_alias, Some(name), _, version) => {
+                let Some(scheme_name) = self.used_schemes.get(scheme_alias.as_str()) else {
+                    self.errors.push(Error::new(
+                        *span,
+                        format!("`{}` is not a used scheme", scheme_alias),
+                    ));
+                    return ProtoType::plain(name.clone());
+                };
+                self.imports.insert(format!("{}.proto", scheme_name));
+                let package = package_name(scheme_name);
+                let name = match version {
+                    Some(version) => version_name(name, version.0),
+                    None => name.clone(),
+                };
+                ProtoType::plain(format!("{}.{}", package, name))
+            }
+            FieldType::Optional(_, inner) => {
+                let inner = self.map_type(inner, subst);
+                match inner.label {
+                    Label::None => ProtoType {
+                        label: Label::Optional,
+                        name: inner.name,
+                    },
+                    _ => ProtoType::plain(self.wrap(inner)),
+                }
+            }
+            FieldType::Reference(..) => ProtoType::plain("uint64"),
+            FieldType::Array(_, inner, _)
+            | FieldType::List(_, inner)
+            | FieldType::Set(_, inner, _) => ProtoType {
+                label: Label::Repeated,
+                name: self.plain_type(inner, subst),
+            },
+            FieldType::Map(_, key_type, value_type, _) => {
+                let key = self.map_type(key_type, subst);
+                let value = self.map_type(value_type, subst);
+                let valid_key = matches!(key.label, Label::None)
+                    && matches!(
+                        key.name.as_str(),
+                        "int32" | "int64" | "uint32" | "uint64" | "bool" | "string"
+                    );
+                if valid_key {
+                    ProtoType {
+                        label: Label::Map(key.name),
+                        name: self.wrap(value),
+                    }
+                } else {
+                    let name = format!("{}{}Entry", key.describe(), value.describe());
+                    if self.synthesized.insert(name.clone()) {
+                        let block = self.message(
+                            &name,
+                            vec![
+                                field_decl(&key, "key", 1, false),
+                                field_decl(&value, "value", 2, false),
+                            ],
+                        );
+                        self.synthesized_blocks.push(block);
+                    }
+                    ProtoType {
+                        label: Label::Repeated,
+                        name,
+                    }
+                }
+            }
+            FieldType::Tuple(_, tuple) => {
+                let items: Vec<ProtoType> = tuple
+                    .0
+                    .iter()
+                    .filter_map(|item| match item {
+                        TupleItem::Field(field) => Some(self.map_type(&field.field_type, subst)),
+                        TupleItem::Include(_) => None,
+                    })
+                    .collect();
+                let name = format!(
+                    "{}Tuple",
+                    items.iter().map(ProtoType::describe).collect::<String>()
+                );
+                if self.synthesized.insert(name.clone()) {
+                    let fields = items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            field_decl(item, &format!("field_{}", index), index + 1, false)
+                        })
+                        .collect();
+                    let block = self.message(&name, fields);
+                    self.synthesized_blocks.push(block);
+                }
+                ProtoType::plain(name)
+            }
+        }
+    }
+
+    fn local_type(
+        &mut self,
+        name: &str,
+        generic_args: &'a [FieldType],
+        version: Option<&MajorVersion>,
+        subst: &Subst<'a>,
+    ) -> ProtoType {
+        let Some(version) = version else {
+            return ProtoType::plain(name);
+        };
+
+        let type_def = self
+            .scheme
+            .types
+            .iter()
+            .find(|type_def| type_def.name() == name && type_def.version() == version);
+        if let Some(Type::Flags(_)) = type_def {
+            return ProtoType::plain("uint64");
+        }
+
+        let mut proto_name = version_name(name, version.0);
+        if let Some(type_def) = type_def.filter(|type_def| !type_def.generics().is_empty()) {
+            let args: Vec<ProtoType> = generic_args
+                .iter()
+                .map(|arg| self.map_type(arg, subst))
+                .collect();
+            for arg in &args {
+                proto_name.push_str(&arg.describe());
+            }
+
+            if self.synthesized.insert(proto_name.clone()) {
+                let subst = type_def
+                    .generics()
+                    .iter()
+                    .map(|param| param.name.as_str())
+                    .zip(args)
+                    .collect();
+                self.pending.push((type_def, proto_name.clone(), subst));
+            }
+        }
+        ProtoType::plain(proto_name)
+    }
+
+    fn plain_type(&mut self, field_type: &'a FieldType, subst: &Subst<'a>) -> String {
+        let proto_type = self.map_type(field_type, subst);
+        self.wrap(proto_type)
+    }
+
+    /// Returns the name of a message holding `proto_type` as its only field
+    /// if it cannot be used as the type of a repeated field or map value.
+    fn wrap(&mut self, proto_type: ProtoType) -> String {
+        if let Label::None = proto_type.label {
+            return proto_type.name;
+        }
+
+        let name = proto_type.describe();
+        if self.synthesized.insert(name.clone()) {
+            let block = self.message(&name, vec![field_decl(&proto_type, "value", 1, false)]);
+            self.synthesized_blocks.push(block);
+        }
+        name
+    }
+
+    fn primitive(&mut self, primitive: &Primitive) -> &'static str {
+        match primitive {
+            Primitive::Int8 | Primitive::Int16 | Primitive::Int32 => "int32",
+            Primitive::Int64 => "int64",
+            Primitive::UInt8 | Primitive::UInt16 | Primitive::UInt32 => "uint32",
+            Primitive::UInt64 => "uint64",
+            Primitive::Float32 => "float",
+            Primitive::Float64 => "double",
+            Primitive::Boolean => "bool",
+            Primitive::Char | Primitive::String | Primitive::Decimal => "string",
+            Primitive::Int128 | Primitive::UInt128 | Primitive::Bytes | Primitive::Uuid => "bytes",
+            Primitive::Timestamp => {
+                self.imports
+                    .insert("google/protobuf/timestamp.proto".to_string());
+                "google.protobuf.Timestamp"
+            }
+            Primitive::Duration => {
+                self.imports
+                    .insert("google/protobuf/duration.proto".to_string());
+                "google.protobuf.Duration"
+            }
+            Primitive::Any => {
+                self.imports.insert("google/protobuf/any.proto".to_string());
+                "google.protobuf.Any"
+            }
+        }
+    }
+}
+
+fn field_decl(proto_type: &ProtoType, name: &str, number: usize, deprecated: bool) -> String {
+    let options = if deprecated {
+        " [deprecated = true]"
+    } else {
+        ""
+    };
+    match &proto_type.label {
+        Label::None => format!("{} {} = {}{};", proto_type.name, name, number, options),
+        Label::Optional => format!(
+            "optional {} {} = {}{};",
+            proto_type.name, name, number, options
+        ),
+        Label::Repeated => format!(
+            "repeated {} {} = {}{};",
+            proto_type.name, name, number, options
+        ),
+        Label::Map(key) => format!(
+            "map<{}, {}> {} = {}{};",
+            key, proto_type.name, name, number, options
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn exports_proto() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            type Id = u64;
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+                next: ?Id,
+            }
+
+            @ver(1)
+            struct User {
+                id: Id,
+                @add(1.1)
+                email: ?str,
+                name: str,
+                tags: [str: [u8]],
+                location: common::Location@ver(1),
+            }
+
+            @ver(2)
+            struct User {
+                @ver(1) {
+                    @rem(tags),
+                },
+                created: timestamp,
+                pair: (u32, str),
+            }
+
+            @ver(1)
+            union Event {
+                Joined(User@ver(2)),
+                @rem(1.1)
+                Left { id: Id, },
+            }
+
+            @ver(1)
+            enum Status {
+                Active = 1,
+                Banned,
+            }
+
+            @ver(1)
+            fn ListUsers (
+                offset: u32,
+            ) -> Page<User@ver(2)>@ver(1)
+        })
+        .unwrap();
+
+        assert_eq!(
+            export(&scheme, |_| None).unwrap(),
+            r#"syntax = "proto3";
+
+package my.users;
+
+import "common.proto";
+import "google/protobuf/timestamp.proto";
+
+message UserV1 {
+  uint64 id = 1;
+  optional string email = 5;
+  string name = 2;
+  map<string, Uint32List> tags = 3;
+  common.LocationV1 location = 4;
+}
+
+message UserV2 {
+  uint64 id = 1;
+  optional string email = 2;
+  string name = 3;
+  common.LocationV1 location = 4;
+  google.protobuf.Timestamp created = 5;
+  Uint32StringTuple pair = 6;
+}
+
+message EventV1 {
+  message Joined {
+    UserV2 field_0 = 1;
+  }
+  message Left {
+    uint64 id = 1;
+  }
+  oneof value {
+    Joined joined = 1;
+    Left left = 2 [deprecated = true];
+  }
+}
+
+enum StatusV1 {
+  STATUS_V1_UNSPECIFIED = 0;
+  STATUS_V1_ACTIVE = 1;
+  STATUS_V1_BANNED = 2;
+}
+
+message ListUsersV1Request {
+  uint32 offset = 1;
+}
+
+message ListUsersV1Response {
+  PageV1UserV2 value = 1;
+}
+
+message Uint32List {
+  repeated uint32 value = 1;
+}
+
+message Uint32StringTuple {
+  uint32 field_0 = 1;
+  string field_1 = 2;
+}
+
+message PageV1UserV2 {
+  repeated UserV2 items = 1;
+  optional uint64 next = 2;
+}
+
+service UsersService {
+  rpc ListUsersV1(ListUsersV1Request) returns (ListUsersV1Response);
+}
+"#
+        );
+    }
+
+    #[test]
+    fn imports_used_schemes_by_name() {
+        let common: Scheme = syn::parse2(quote! {
+            sch "shared/common";
+
+            @ver(1)
+            struct Location {
+                lat: f64,
+                lon: f64,
+            }
+        })
+        .unwrap();
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            @ver(1)
+            struct User {
+                location: common::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            export(&scheme, |_| Some(&common)).unwrap(),
+            r#"syntax = "proto3";
+
+package my.users;
+
+import "shared/common.proto";
+
+message UserV1 {
+  shared.common.LocationV1 location = 1;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn starts_enums_with_zero() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            enum Status {
+                Active = 1,
+                Unknown = 0,
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            export(&scheme, |_| None).unwrap(),
+            r#"syntax = "proto3";
+
+package my.users;
+
+enum StatusV1 {
+  STATUS_V1_UNKNOWN = 0;
+  STATUS_V1_ACTIVE = 1;
+}
+"#
+        );
+
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            enum Status {
+                Active = 2147483647,
+                Banned,
+            }
+        })
+        .unwrap();
+
+        let err = export(&scheme, |_| None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Banned` has the value 2147483648, but proto3 enum values must be at most 2147483647"
+        );
+    }
+
+    #[test]
+    fn rejects_unused_schemes() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            struct User {
+                location: nope::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        let err = export(&scheme, |_| None).unwrap_err();
+        assert_eq!(err.to_string(), "`nope` is not a used scheme");
+    }
+}
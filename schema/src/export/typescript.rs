@@ -0,0 +1,558 @@
+use std::collections::{BTreeMap, HashMap};
+
+use syn::{Error, Result};
+
+use crate::{
+    export::{used_scheme_names, version_name},
+    parse::{
+        Const, ConstValue, Enum, Errors, FieldType, GenericParam, MajorVersion, Object, Primitive,
+        Scheme, StructBody, StructItem, Tuple, TupleItem, Type, TypeAlias, Union, Use,
+    },
+    resolve::{resolve, Member},
+};
+
+/// Exports `scheme` as TypeScript declarations of the values `JSON.parse`
+/// returns for serde's JSON representation, the same values the
+/// [`json_schema`](super::json_schema) export describes.
+///
+/// Every type version becomes a type named after the type and its major
+/// version, e.g. `UserV2`. Structs become interfaces, with optional fields
+/// and fields added in a minor version marked optional, and tuple structs
+/// become tuple types. Unions are externally tagged: a unit variant is its
+/// name and other variants are an object with the variant name as its only
+/// property. Enums become unions of string literals. Functions get a
+/// `Request` interface for their parameters and a `Response` type for their
+/// return value.
+///
+/// Integers, flags and object references are `number`s, so integers beyond
+/// `Number.MAX_SAFE_INTEGER` lose precision when parsed. `bytes` is an array
+/// of numbers, timestamps, durations, UUIDs and decimals are strings, and
+/// sets are arrays. Maps are objects when their keys are written as strings
+/// and arrays of `[key, value]` pairs otherwise.
+///
+/// A scheme named `a/b/types` is expected to be exported to the module
+/// `a/b/types`, and a used scheme is imported from the module named after
+/// it in the same way. `find_scheme` looks up the scheme a `use` item brings
+/// into scope, see [`used_scheme_names`].
+pub fn export<'a>(
+    scheme: &Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'a Scheme>,
+) -> Result<String> {
+    let mut exporter = Exporter {
+        scheme,
+        used_schemes: used_scheme_names(scheme, find_scheme),
+        aliases: scheme
+            .aliases
+            .iter()
+            .map(|alias| (alias.name.as_str(), alias))
+            .collect(),
+        imports: BTreeMap::new(),
+        errors: Errors::default(),
+    };
+
+    let mut blocks = Vec::new();
+    for const_ in &scheme.consts {
+        blocks.push(const_decl(const_));
+    }
+    for alias in &scheme.aliases {
+        let alias_type = exporter.field_type(&alias.field_type);
+        blocks.push(format!("export type {} = {};\n", alias.name, alias_type));
+    }
+    for type_def in &scheme.types {
+        blocks.push(exporter.type_decl(type_def));
+    }
+    exporter.errors.finish()?;
+
+    let mut ts = String::new();
+    for (alias, path) in &exporter.imports {
+        ts.push_str(&format!("import type * as {} from \"{}\";\n", alias, path));
+    }
+    for (index, block) in blocks.iter().enumerate() {
+        if index > 0 || !exporter.imports.is_empty() {
+            ts.push('\n');
+        }
+        ts.push_str(block);
+    }
+    Ok(ts)
+}
+
+fn const_decl(const_: &Const) -> String {
+    let value = match &const_.value {
+        ConstValue::Int(value) => value.to_string(),
+        ConstValue::UInt(value) => value.to_string(),
+        ConstValue::Float(value) if value.is_finite() => format!("{:?}", value),
+        // TypeScript has no literal types for `Infinity` and `NaN`.
+        ConstValue::Float(_) => "number".to_string(),
+        ConstValue::Bool(value) => value.to_string(),
+        ConstValue::Char(value) => format!("{:?}", value.to_string()),
+        ConstValue::String(value) => format!("{:?}", value),
+    };
+    format!("export declare const {}: {};\n", const_.name, value)
+}
+
+struct Exporter<'a> {
+    scheme: &'a Scheme,
+    used_schemes: HashMap<&'a str, String>,
+    aliases: HashMap<&'a str, &'a TypeAlias>,
+    imports: BTreeMap<&'a str, String>,
+    errors: Errors,
+}
+
+/// How serde writes map keys as JSON object property names.
+enum KeyNames {
+    Strings,
+    /// The field names of an enum.
+    Enum,
+}
+
+impl<'a> Exporter<'a> {
+    fn type_decl(&mut self, type_def: &'a Type) -> String {
+        let name = version_name(type_def.name(), type_def.version().0);
+        match type_def {
+            Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => {
+                let name = format!("{}{}", name, generics(&struct_.generics));
+                match &struct_.body {
+                    StructBody::Items(items) => {
+                        let fields = self.struct_fields(&struct_.name, &struct_.version, items, "");
+                        format!("export interface {} {{\n{}}}\n", name, fields)
+                    }
+                    body => format!(
+                        "export type {} = {};\n",
+                        name,
+                        self.body_type(&struct_.name, &struct_.version, body)
+                    ),
+                }
+            }
+            Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+                let name = format!("{}{}", name, generics(&union_.generics));
+                format!("export type {} =\n{};\n", name, self.union_variants(union_))
+            }
+            Type::Enum(enum_) | Type::Object(Object::Enum(enum_)) => {
+                format!("export type {} = {};\n", name, self.enum_names(enum_))
+            }
+            Type::Flags(_) => format!("export type {} = number;\n", name),
+            Type::Function(function) => {
+                let fields =
+                    self.struct_fields(&function.name, &function.version, &function.items, "");
+                let response = match &function.return_type {
+                    Some(return_type) => self.field_type(return_type),
+                    None => "null".to_string(),
+                };
+                format!(
+                    "export interface {}Request {{\n{}}}\n\nexport type {}Response = {};\n",
+                    name, fields, name, response
+                )
+            }
+            Type::Command(command) => {
+                let fields =
+                    self.struct_fields(&command.name, &command.version, &command.items, "");
+                format!("export interface {} {{\n{}}}\n", name, fields)
+            }
+        }
+    }
+
+    /// Returns the fields of a struct body, one per line, each prefixed by
+    /// `indent`.
+    fn struct_fields(
+        &mut self,
+        name: &str,
+        version: &MajorVersion,
+        items: &'a [StructItem],
+        indent: &str,
+    ) -> String {
+        let mut fields = String::new();
+        for member in resolve(self.scheme, name, version, items) {
+            let field = member.field;
+            let is_added = member.included_from.is_none() && field.added().is_some();
+            let is_optional = is_added || matches!(field.field_type, FieldType::Optional(..));
+            fields.push_str(&format!(
+                "{}  {}{}: {};\n",
+                indent,
+                field.name,
+                if is_optional { "?" } else { "" },
+                self.field_type(&field.field_type)
+            ));
+        }
+        fields
+    }
+
+    /// Returns the type of a tuple or unit struct body.
+    fn body_type(&mut self, name: &str, version: &MajorVersion, body: &'a StructBody) -> String {
+        match body {
+            StructBody::Items(_) => unreachable!("struct bodies are interfaces"),
+            StructBody::Tuple(tuple) => {
+                let members = resolve(self.scheme, name, version, &tuple.0);
+                if let [member] = &members[..] {
+                    return self.field_type(&member.field.field_type);
+                }
+
+                let items: Vec<String> = members
+                    .iter()
+                    .map(|member| {
+                        let is_added =
+                            member.included_from.is_none() && member.field.added().is_some();
+                        let item = self.field_type(&member.field.field_type);
+                        if is_added {
+                            format!("{}?", item)
+                        } else {
+                            item
+                        }
+                    })
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+            StructBody::Unit => "null".to_string(),
+        }
+    }
+
+    fn union_variants(&mut self, union_: &'a Union) -> String {
+        let mut variants = Vec::new();
+        for member in resolve(self.scheme, &union_.name, &union_.version, &union_.items) {
+            let variant = member.field;
+            let version = member.included_from.unwrap_or(&union_.version);
+            variants.push(match &variant.body {
+                StructBody::Items(items) => format!(
+                    "  | {{\n      {}: {{\n{}      }};\n    }}",
+                    variant.name,
+                    self.struct_fields(&union_.name, version, items, "      ")
+                ),
+                StructBody::Tuple(_) => format!(
+                    "  | {{ {}: {} }}",
+                    variant.name,
+                    self.body_type(&union_.name, version, &variant.body)
+                ),
+                StructBody::Unit => format!("  | \"{}\"", variant.name),
+            });
+        }
+        if variants.is_empty() {
+            return "  never".to_string();
+        }
+        variants.join("\n")
+    }
+
+    fn enum_names(&mut self, enum_: &'a Enum) -> String {
+        let names: Vec<String> = resolve(self.scheme, &enum_.name, &enum_.version, &enum_.items)
+            .iter()
+            .map(|member| format!("\"{}\"", member.field.name))
+            .collect();
+        if names.is_empty() {
+            "never".to_string()
+        } else {
+            names.join(" | ")
+        }
+    }
+
+    fn field_type(&mut self, field_type: &'a FieldType) -> String {
+        match field_type {
+            FieldType::Primitive(_, primitive) => primitive_type(primitive).to_string(),
+            FieldType::Type(span, name, extern_name, generic_args, version) => {
+                let local_name = extern_name.as_ref().unwrap_or(name);
+                let mut type_name = match version {
+                    Some(version) => version_name(local_name, version.0),
+                    None => local_name.clone(),
+                };
+                if extern_name.is_some() {
+                    match self.used_schemes.get(name.as_str()) {
+                        Some(scheme_name) => {
+                            self.imports.insert(name, scheme_name.clone());
+                        }
+                        None => self.errors.push(Error::new(
+                            *span,
+                            format!("`{}` is not a used scheme", name),
+                        )),
+                    }
+                    type_name = format!("{}.{}", name, type_name);
+                }
+                if !generic_args.is_empty() {
+                    let args: Vec<String> = generic_args
+                        .iter()
+                        .map(|arg| self.field_type(arg))
+                        .collect();
+                    type_name = format!("{}<{}>", type_name, args.join(", "));
+                }
+                type_name
+            }
+            FieldType::Optional(_, inner) => format!("{} | null", self.field_type(inner)),
+            FieldType::Reference(..) => "number".to_string(),
+            FieldType::Array(_, inner, _)
+            | FieldType::List(_, inner)
+            | FieldType::Set(_, inner, _) => {
+                let inner = self.field_type(inner);
+                if inner.contains(' ') {
+                    format!("({})[]", inner)
+                } else {
+                    format!("{}[]", inner)
+                }
+            }
+            FieldType::Map(_, key_type, value_type, _) => {
+                let value = self.field_type(value_type);
+                match self.key_names(key_type) {
+                    Some(KeyNames::Strings) => format!("Record<string, {}>", value),
+                    Some(KeyNames::Enum) => {
+                        format!("Partial<Record<{}, {}>>", self.field_type(key_type), value)
+                    }
+                    None => format!("[{}, {}][]", self.field_type(key_type), value),
+                }
+            }
+            FieldType::Tuple(_, Tuple(items)) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .filter_map(|item| match item {
+                        TupleItem::Field(field) => Some(self.field_type(&field.field_type)),
+                        TupleItem::Include(_) => None,
+                    })
+                    .collect();
+                format!("[{}]", items.join(", "))
+            }
+        }
+    }
+
+    /// Returns how serde writes keys of `key_type` as property names, or
+    /// `None` if it cannot write them as property names.
+    fn key_names(&self, key_type: &FieldType) -> Option<KeyNames> {
+        match key_type {
+            FieldType::Primitive(_, primitive) if primitive.int_range().is_some() => {
+                Some(KeyNames::Strings)
+            }
+            FieldType::Primitive(
+                _,
+                Primitive::Char
+                | Primitive::String
+                | Primitive::Timestamp
+                | Primitive::Duration
+                | Primitive::Uuid
+                | Primitive::Decimal,
+            ) => Some(KeyNames::Strings),
+            FieldType::Type(_, name, None, generic_args, None) if generic_args.is_empty() => {
+                let alias = self.aliases.get(name.as_str())?;
+                self.key_names(&alias.field_type)
+            }
+            FieldType::Type(_, name, None, _, Some(version)) => {
+                let is_enum = self.scheme.types.iter().any(|type_def| {
+                    type_def.name() == name
+                        && type_def.version() == version
+                        && matches!(type_def, Type::Enum(_) | Type::Object(Object::Enum(_)))
+                });
+                is_enum.then_some(KeyNames::Enum)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn generics(params: &[GenericParam]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let names: Vec<&str> = params.iter().map(|param| param.name.as_str()).collect();
+    format!("<{}>", names.join(", "))
+}
+
+fn primitive_type(primitive: &Primitive) -> &'static str {
+    match primitive {
+        Primitive::Int8
+        | Primitive::Int16
+        | Primitive::Int32
+        | Primitive::UInt8
+        | Primitive::UInt16
+        | Primitive::UInt32
+        | Primitive::Int64
+        | Primitive::Int128
+        | Primitive::UInt64
+        | Primitive::UInt128
+        | Primitive::Float32
+        | Primitive::Float64 => "number",
+        Primitive::Boolean => "boolean",
+        Primitive::Char
+        | Primitive::String
+        | Primitive::Timestamp
+        | Primitive::Duration
+        | Primitive::Uuid
+        | Primitive::Decimal => "string",
+        Primitive::Bytes => "number[]",
+        Primitive::Any => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn exports_typescript() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            const NAME_LEN: u32 = 8;
+            const MAX_ID: u64 = 1000;
+            type Id = u64;
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+                next: ?Id,
+            }
+
+            @ver(1)
+            struct User {
+                id: Id,
+                name: [char; NAME_LEN],
+                @add(1.1)
+                email: ?str,
+                scores: [Status@ver(1): f64],
+                names: [u64: str],
+                edges: [(u8, u8): str],
+                tags: {str},
+                avatar: bytes,
+                created: timestamp,
+                pair: (u8, str),
+                location: common::Location@ver(1),
+            }
+
+            @ver(1)
+            struct UserId (
+                Id,
+            )
+
+            @ver(1)
+            union Event {
+                Joined(User@ver(1)),
+                Moved {
+                    from: common::Location@ver(1),
+                    to: common::Location@ver(1),
+                },
+                @rem(1.1)
+                Left,
+            }
+
+            @ver(1)
+            enum Status {
+                Active,
+                Banned,
+            }
+
+            @ver(1)
+            fn ListUsers (
+                offset: u32,
+            ) -> Page<User@ver(1)>@ver(1)
+        })
+        .unwrap();
+
+        assert_eq!(
+            export(&scheme, |_| None).unwrap(),
+            r#"import type * as common from "common";
+
+export declare const NAME_LEN: 8;
+
+export declare const MAX_ID: 1000;
+
+export type Id = number;
+
+export interface PageV1<T> {
+  items: T[];
+  next?: Id | null;
+}
+
+export interface UserV1 {
+  id: Id;
+  name: string[];
+  email?: string | null;
+  scores: Partial<Record<StatusV1, number>>;
+  names: Record<string, string>;
+  edges: [[number, number], string][];
+  tags: string[];
+  avatar: number[];
+  created: string;
+  pair: [number, string];
+  location: common.LocationV1;
+}
+
+export type UserIdV1 = Id;
+
+export type EventV1 =
+  | { Joined: UserV1 }
+  | {
+      Moved: {
+        from: common.LocationV1;
+        to: common.LocationV1;
+      };
+    }
+  | "Left";
+
+export type StatusV1 = "Active" | "Banned";
+
+export interface ListUsersV1Request {
+  offset: number;
+}
+
+export type ListUsersV1Response = PageV1<UserV1>;
+"#
+        );
+    }
+
+    #[test]
+    fn imports_used_schemes_by_name() {
+        let common: Scheme = syn::parse2(quote! {
+            sch "shared/common";
+        })
+        .unwrap();
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            use crate::common;
+
+            @ver(1)
+            struct User {
+                location: common::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            export(&scheme, |_| Some(&common)).unwrap(),
+            r#"import type * as common from "shared/common";
+
+export interface UserV1 {
+  location: common.LocationV1;
+}
+"#
+        );
+    }
+
+    #[test]
+    fn rejects_unused_schemes() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            struct User {
+                location: nope::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        let err = export(&scheme, |_| None).unwrap_err();
+        assert_eq!(err.to_string(), "`nope` is not a used scheme");
+    }
+
+    #[test]
+    fn declares_non_finite_constants_as_numbers() {
+        let const_ = |value| Const {
+            name_span: proc_macro2::Span::call_site(),
+            name: "A".to_string(),
+            const_type: Primitive::Float64,
+            value: ConstValue::Float(value),
+        };
+
+        assert_eq!(const_decl(&const_(0.5)), "export declare const A: 0.5;\n");
+        for value in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+            assert_eq!(
+                const_decl(&const_(value)),
+                "export declare const A: number;\n"
+            );
+        }
+    }
+}
@@ -0,0 +1,642 @@
+use syn::{Error, Result};
+
+use crate::parse::{
+    ArraySize, Collection, Const, ConstValue, Enum, EnumItem, Errors, FieldType, Flags, FlagsItem,
+    Function, GenericParam, Include, IncludeItem, MajorVersion, MinorVersion, Object, Primitive,
+    Scheme, Struct, StructBody, StructItem, Tuple, TupleItem, Type, TypeAlias, Union, UnionItem,
+    Use,
+};
+
+/// Formats `scheme` as canonically formatted schema source.
+///
+/// Parsing the output yields a scheme equal to `scheme`. Items are written
+/// in the order uses, constants, aliases and types, separated by blank
+/// lines, and bodies are indented by four spaces with trailing commas.
+///
+/// Float constants that are infinite or NaN have no literal to write them
+/// as, and are errors.
+pub fn format(scheme: &Scheme) -> Result<String> {
+    let mut out = format!("sch {:?};\n", scheme.name);
+
+    if !scheme.uses.is_empty() {
+        out.push('\n');
+        for use_ in &scheme.uses {
+            out.push_str(&format_use(use_));
+        }
+    }
+    if !scheme.consts.is_empty() {
+        let mut errors = Errors::default();
+        for const_ in &scheme.consts {
+            if matches!(const_.value, ConstValue::Float(value) if !value.is_finite()) {
+                errors.push(Error::new(
+                    const_.name_span,
+                    format!("`{}` is not finite and cannot be formatted", const_.name),
+                ));
+            }
+        }
+        errors.finish()?;
+
+        out.push('\n');
+        for const_ in &scheme.consts {
+            out.push_str(&format_const(const_));
+        }
+    }
+    if !scheme.aliases.is_empty() {
+        out.push('\n');
+        for alias in &scheme.aliases {
+            out.push_str(&format_alias(alias));
+        }
+    }
+    for type_def in &scheme.types {
+        out.push('\n');
+        write_type(&mut out, type_def);
+    }
+    Ok(out)
+}
+
+fn format_use(use_: &Use) -> String {
+    match &use_.alias {
+        Some(alias) => format!("use {} as {};\n", use_.segments.join("::"), alias),
+        None => format!("use {};\n", use_.segments.join("::")),
+    }
+}
+
+fn format_const(const_: &Const) -> String {
+    format!(
+        "const {}: {} = {};\n",
+        const_.name,
+        primitive_name(&const_.const_type),
+        format_const_value(&const_.value)
+    )
+}
+
+fn format_alias(alias: &TypeAlias) -> String {
+    format!(
+        "type {} = {};\n",
+        alias.name,
+        format_field_type(&alias.field_type)
+    )
+}
+
+fn write_type(out: &mut String, type_def: &Type) {
+    out.push_str(&format!("@ver({})\n", type_def.version().0));
+    match type_def {
+        Type::Object(object) => {
+            let (kind, rest) = match object {
+                Object::Struct(struct_) => ("struct", format_struct(struct_)),
+                Object::Union(union_) => ("union", format_union(union_)),
+                Object::Enum(enum_) => ("enum", format_enum(enum_)),
+            };
+            out.push_str(&format!("obj({}) {}", kind, rest));
+        }
+        Type::Struct(struct_) => out.push_str(&format!("struct {}", format_struct(struct_))),
+        Type::Union(union_) => out.push_str(&format!("union {}", format_union(union_))),
+        Type::Enum(enum_) => out.push_str(&format!("enum {}", format_enum(enum_))),
+        Type::Flags(flags) => out.push_str(&format!("flags {}", format_flags(flags))),
+        Type::Function(function) => out.push_str(&format!("fn {}", format_function(function))),
+        Type::Command(command) => out.push_str(&format!(
+            "cmd {} {}",
+            command.name,
+            format_items('(', ')', &command.items, 0)
+        )),
+    }
+    out.push('\n');
+}
+
+fn format_struct(struct_: &Struct) -> String {
+    let body = match &struct_.body {
+        StructBody::Unit => ";".to_string(),
+        body => format!(" {}", format_body(body, 0)),
+    };
+    format!(
+        "{}{}{}",
+        struct_.name,
+        format_generics(&struct_.generics),
+        body
+    )
+}
+
+fn format_union(union_: &Union) -> String {
+    let items: Vec<String> = union_
+        .items
+        .iter()
+        .map(|item| match item {
+            UnionItem::Include(include) => format_include(include, 1),
+            UnionItem::Field(field) => {
+                let body = match &field.body {
+                    StructBody::Unit => String::new(),
+                    StructBody::Tuple(tuple) => format_tuple_body(tuple, 1),
+                    body => format!(" {}", format_body(body, 1)),
+                };
+                format!(
+                    "{}{}{}",
+                    format_directive("rem", &field.version, 1),
+                    field.name,
+                    body
+                )
+            }
+        })
+        .collect();
+    format!(
+        "{}{} {}",
+        union_.name,
+        format_generics(&union_.generics),
+        block('{', '}', &items, 0)
+    )
+}
+
+fn format_enum(enum_: &Enum) -> String {
+    let items: Vec<String> = enum_
+        .items
+        .iter()
+        .map(|item| match item {
+            EnumItem::Include(include) => format_include(include, 1),
+            EnumItem::Field(field) => {
+                let value = match field.value {
+                    Some(value) => format!(" = {}", value),
+                    None => String::new(),
+                };
+                format!(
+                    "{}{}{}",
+                    format_directive("rem", &field.version, 1),
+                    field.name,
+                    value
+                )
+            }
+        })
+        .collect();
+    format!("{} {}", enum_.name, block('{', '}', &items, 0))
+}
+
+fn format_flags(flags: &Flags) -> String {
+    let items: Vec<String> = flags
+        .items
+        .iter()
+        .map(|item| match item {
+            FlagsItem::Include(include) => format_include(include, 1),
+            FlagsItem::Field(field) => {
+                let mut directives = String::new();
+                for (directive, version) in [("add", &field.added), ("rem", &field.removed)] {
+                    if let Some(MinorVersion(major, minor)) = version {
+                        directives.push_str(&format!("@{}({}.{}) ", directive, major, minor));
+                    }
+                }
+                if !directives.is_empty() {
+                    directives.pop();
+                    directives.push('\n');
+                    directives.push_str(&indent(1));
+                }
+                format!("{}{} = {}", directives, field.name, field.bit)
+            }
+        })
+        .collect();
+    format!("{} {}", flags.name, block('{', '}', &items, 0))
+}
+
+fn format_function(function: &Function) -> String {
+    let return_type = match &function.return_type {
+        Some(return_type) => format!(" -> {}", format_field_type(return_type)),
+        None => String::new(),
+    };
+    format!(
+        "{} {}{}",
+        function.name,
+        format_items('(', ')', &function.items, 0),
+        return_type
+    )
+}
+
+fn format_body(body: &StructBody, level: usize) -> String {
+    match body {
+        StructBody::Items(items) => format_items('{', '}', items, level),
+        StructBody::Tuple(tuple) => format_tuple_body(tuple, level),
+        StructBody::Unit => String::new(),
+    }
+}
+
+fn format_items(open: char, close: char, items: &[StructItem], level: usize) -> String {
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| match item {
+            StructItem::Include(include) => format_include(include, level + 1),
+            StructItem::Field(field) => format!(
+                "{}{}: {}",
+                format_directive("add", &field.version, level + 1),
+                field.name,
+                format_field_type(&field.field_type)
+            ),
+        })
+        .collect();
+    block(open, close, &items, level)
+}
+
+/// Formats the fields of a tuple struct or union field, on a single line
+/// unless they contain directives or includes.
+fn format_tuple_body(tuple: &Tuple, level: usize) -> String {
+    let is_plain = tuple.0.iter().all(|item| {
+        matches!(
+            item,
+            TupleItem::Field(field) if field.version.is_none()
+        )
+    });
+    if is_plain {
+        return format_tuple(tuple);
+    }
+
+    let items: Vec<String> = tuple
+        .0
+        .iter()
+        .map(|item| match item {
+            TupleItem::Include(include) => format_include(include, level + 1),
+            TupleItem::Field(field) => format!(
+                "{}{}",
+                format_directive("add", &field.version, level + 1),
+                format_field_type(&field.field_type)
+            ),
+        })
+        .collect();
+    block('(', ')', &items, level)
+}
+
+fn format_tuple(tuple: &Tuple) -> String {
+    let items: Vec<String> = tuple
+        .0
+        .iter()
+        .map(|item| match item {
+            TupleItem::Include(include) => {
+                format!(
+                    "@ver({}){}",
+                    include.version.0,
+                    format_include_items(include)
+                )
+            }
+            TupleItem::Field(field) => {
+                let directive = match &field.version {
+                    Some(MinorVersion(major, minor)) => format!("@add({}.{}) ", major, minor),
+                    None => String::new(),
+                };
+                format!("{}{}", directive, format_field_type(&field.field_type))
+            }
+        })
+        .collect();
+    format!("({})", items.join(", "))
+}
+
+fn format_include(include: &Include, level: usize) -> String {
+    if include.items.is_empty() {
+        return format!("@ver({})", include.version.0);
+    }
+
+    let items: Vec<String> = include.items.iter().map(format_include_item).collect();
+    format!(
+        "@ver({}) {}",
+        include.version.0,
+        block('{', '}', &items, level)
+    )
+}
+
+fn format_include_items(include: &Include) -> String {
+    if include.items.is_empty() {
+        return String::new();
+    }
+
+    let items: Vec<String> = include
+        .items
+        .iter()
+        .map(|item| format!("{},", format_include_item(item)))
+        .collect();
+    format!(" {{ {} }}", items.join(" "))
+}
+
+fn format_include_item(item: &IncludeItem) -> String {
+    match item {
+        IncludeItem::Add(name) => format!("@add({})", name),
+        IncludeItem::Rem(name) => format!("@rem({})", name),
+    }
+}
+
+/// Formats a minor version directive on its own line, followed by the
+/// indentation of the item it belongs to.
+fn format_directive(directive: &str, version: &Option<MinorVersion>, level: usize) -> String {
+    match version {
+        Some(MinorVersion(major, minor)) => {
+            format!("@{}({}.{})\n{}", directive, major, minor, indent(level))
+        }
+        None => String::new(),
+    }
+}
+
+fn format_generics(params: &[GenericParam]) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let names: Vec<&str> = params.iter().map(|param| param.name.as_str()).collect();
+    format!("<{}>", names.join(", "))
+}
+
+/// Formats `items` one per line with trailing commas, indented one level
+/// deeper than `level`.
+fn block(open: char, close: char, items: &[String], level: usize) -> String {
+    if items.is_empty() {
+        return format!("{}{}", open, close);
+    }
+
+    let mut out = String::new();
+    out.push(open);
+    out.push('\n');
+    for item in items {
+        out.push_str(&indent(level + 1));
+        out.push_str(item);
+        out.push_str(",\n");
+    }
+    out.push_str(&indent(level));
+    out.push(close);
+    out
+}
+
+fn indent(level: usize) -> String {
+    "    ".repeat(level)
+}
+
+/// Formats a field type as it is written in schema source.
+pub fn format_field_type(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Primitive(_, primitive) => primitive_name(primitive).to_string(),
+        FieldType::Type(_, name, extern_name, generic_args, version) => {
+            let mut out = name.clone();
+            if let Some(extern_name) = extern_name {
+                out.push_str("::");
+                out.push_str(extern_name);
+            }
+            if !generic_args.is_empty() {
+                let args: Vec<String> = generic_args.iter().map(format_field_type).collect();
+                out.push_str(&format!("<{}>", args.join(", ")));
+            }
+            if let Some(MajorVersion(version)) = version {
+                out.push_str(&format!("@ver({})", version));
+            }
+            out
+        }
+        FieldType::Optional(_, inner) => format!("?{}", format_field_type(inner)),
+        FieldType::Reference(_, inner) => format!("&{}", format_field_type(inner)),
+        FieldType::Array(_, inner, size) => {
+            let size = match size {
+                ArraySize::Value(size) => size.to_string(),
+                ArraySize::Const(_, name) => name.clone(),
+            };
+            format!("[{}; {}]", format_field_type(inner), size)
+        }
+        FieldType::List(_, inner) => format!("[{}]", format_field_type(inner)),
+        FieldType::Map(_, key_type, value_type, collection) => format!(
+            "[{}: {}{}]",
+            format_field_type(key_type),
+            format_field_type(value_type),
+            format_collection(collection)
+        ),
+        FieldType::Set(_, inner, collection) => format!(
+            "{{{}{}}}",
+            format_field_type(inner),
+            format_collection(collection)
+        ),
+        FieldType::Tuple(_, tuple) => format_tuple(tuple),
+    }
+}
+
+fn format_collection(collection: &Collection) -> &'static str {
+    match collection {
+        Collection::Hashed => "",
+        Collection::Ordered => "; ord",
+    }
+}
+
+/// Formats a constant value as it is written in schema source.
+pub fn format_const_value(value: &ConstValue) -> String {
+    match value {
+        ConstValue::Int(value) => value.to_string(),
+        ConstValue::UInt(value) => value.to_string(),
+        ConstValue::Float(value) => format!("{:?}", value),
+        ConstValue::Bool(value) => value.to_string(),
+        ConstValue::Char(value) => format!("{:?}", value),
+        ConstValue::String(value) => format!("{:?}", value),
+    }
+}
+
+/// Returns the keyword `primitive` is written as in schema source.
+pub fn primitive_name(primitive: &Primitive) -> &'static str {
+    match primitive {
+        Primitive::Int8 => "i8",
+        Primitive::Int16 => "i16",
+        Primitive::Int32 => "i32",
+        Primitive::Int64 => "i64",
+        Primitive::Int128 => "i128",
+        Primitive::UInt8 => "u8",
+        Primitive::UInt16 => "u16",
+        Primitive::UInt32 => "u32",
+        Primitive::UInt64 => "u64",
+        Primitive::UInt128 => "u128",
+        Primitive::Float32 => "f32",
+        Primitive::Float64 => "f64",
+        Primitive::Boolean => "bool",
+        Primitive::Char => "char",
+        Primitive::String => "str",
+        Primitive::Bytes => "bytes",
+        Primitive::Timestamp => "timestamp",
+        Primitive::Duration => "duration",
+        Primitive::Uuid => "uuid",
+        Primitive::Decimal => "decimal",
+        Primitive::Any => "any",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn round_trips() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            use external_crate::some_path::scheme as extern_scheme;
+            use crate::some_path::types;
+
+            const MAX_NAME: u32 = 64;
+            const MIN_SCORE: i16 = -10;
+            const RATIO: f64 = 0.5;
+            const BIG: f64 = 1e300;
+            const ENABLED: bool = true;
+            const SEPARATOR: char = '\'';
+            const GREETING: str = "hello \"world\"\n";
+            type UserId = u64;
+            type Scores = [str: [f32]; ord];
+
+            @ver(1)
+            struct Struct<T, E> {
+                a: i8, b: i16, c: i32, d: i64, e: i128,
+                f: u8, g: u16, h: u32, i: u64, j: u128,
+                k: f32, l: f64, m: bool, n: char, o: str, p: bytes,
+                q: timestamp, r: duration, s: uuid, t: decimal, u: any,
+                optional: ?T,
+                reference: &types::Object,
+                array: [u8; 32],
+                const_array: [char; MAX_NAME],
+                list: [E],
+                map: [u8: u8],
+                set: {str; ord},
+                tuple: (u8, @add(1.1) u16),
+                generic: Struct<u8, Result<str, bool>@ver(1)>@ver(1),
+                @add(1.1)
+                added: extern_scheme::Struct@ver(2),
+            }
+
+            @ver(2)
+            struct Struct {
+                @ver(1) {
+                    @rem(list),
+                    @add(added),
+                },
+                @ver(1),
+            }
+
+            @ver(1)
+            struct Unit;
+
+            @ver(1)
+            struct Point(i32, i32)
+
+            @ver(1)
+            struct TupleStruct (
+                types::Struct@ver(2),
+                @add(1.1)
+                types::Union@ver(1),
+            )
+
+            @ver(1)
+            union Result<T, E> {
+                Ok(T),
+                Err(E),
+                Tuple(
+                    u8,
+                    @add(1.1)
+                    u16,
+                ),
+                Struct {
+                    new_type: Point@ver(1),
+                },
+                @rem(1.2)
+                None,
+                Empty {},
+            }
+
+            @ver(1)
+            enum Enum {
+                @rem(1.1)
+                Zero,
+                One = 10,
+            }
+
+            @ver(1)
+            flags Permissions {
+                Read = 0,
+                @add(1.1) @rem(1.2)
+                Write = 1,
+            }
+
+            @ver(2)
+            flags Permissions {
+                @ver(1) {
+                    @add(Write),
+                },
+                Admin = 2,
+            }
+
+            @ver(1)
+            obj(struct) Object {
+                struct_: Struct@ver(2),
+            }
+
+            @ver(2)
+            obj(union) Object {
+                None,
+            }
+
+            @ver(3)
+            obj(enum) Object {
+                Zero,
+            }
+
+            @ver(1)
+            fn Function (
+                one: Struct@ver(2),
+                two: &Object,
+            ) -> Struct@ver(2)
+
+            @ver(1)
+            fn Ping ()
+
+            @ver(1)
+            cmd Command (
+                one: &Object,
+            )
+        })
+        .unwrap();
+
+        let formatted = format(&scheme).unwrap();
+        let reparsed: Scheme = syn::parse_str(&formatted).unwrap();
+        assert_eq!(reparsed, scheme);
+        assert_eq!(format(&reparsed).unwrap(), formatted);
+    }
+
+    #[test]
+    fn formats_canonically() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+            type Id = u64;
+            use crate::types;
+            @ver(1) union Event { Joined(Id, types::User@ver(1)), @rem(1.1) Left { id: Id, @add(1.1) reason: ?str, }, }
+            @ver(1) flags Permissions { @add(1.1) Read = 0, }
+        })
+        .unwrap();
+
+        assert_eq!(
+            format(&scheme).unwrap(),
+            r#"sch "scheme/name";
+
+use crate::types;
+
+type Id = u64;
+
+@ver(1)
+union Event {
+    Joined(Id, types::User@ver(1)),
+    @rem(1.1)
+    Left {
+        id: Id,
+        @add(1.1)
+        reason: ?str,
+    },
+}
+
+@ver(1)
+flags Permissions {
+    @add(1.1)
+    Read = 0,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn rejects_non_finite_constants() {
+        let mut scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+            const A: f64 = 0.5;
+        })
+        .unwrap();
+        scheme.consts[0].value = ConstValue::Float(f64::NAN);
+
+        let err = format(&scheme).unwrap_err();
+        assert_eq!(err.to_string(), "`A` is not finite and cannot be formatted");
+    }
+}
@@ -0,0 +1,7 @@
+pub mod diff;
+pub mod export;
+pub mod format;
+pub mod parse;
+pub mod resolve;
+pub mod validate;
+pub mod value;
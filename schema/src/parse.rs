@@ -1,11 +1,12 @@
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenTree};
 use syn::{
     braced, bracketed,
+    buffer::Cursor,
     ext::IdentExt,
     parenthesized,
     parse::{Parse, ParseStream},
     token::{Brace, Bracket, Paren},
-    Error, Ident, LitFloat, LitInt, LitStr, Result, Token,
+    Error, Ident, LitBool, LitChar, LitFloat, LitInt, LitStr, Result, Token,
 };
 
 mod kw {
@@ -15,14 +16,18 @@ mod kw {
     syn::custom_keyword!(rem);
     syn::custom_keyword!(obj);
     syn::custom_keyword!(cmd);
+    syn::custom_keyword!(flags);
     syn::custom_keyword!(any);
+    syn::custom_keyword!(ord);
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Scheme {
-    name: String,
-    uses: Vec<Use>,
-    types: Vec<Type>,
+    pub name: String,
+    pub uses: Vec<Use>,
+    pub consts: Vec<Const>,
+    pub aliases: Vec<TypeAlias>,
+    pub types: Vec<Type>,
 }
 
 impl Parse for Scheme {
@@ -34,69 +39,111 @@ impl Parse for Scheme {
             name.value()
         };
 
-        let mut uses = Vec::new();
-        let mut types = Vec::new();
+        let mut scheme = Self {
+            name,
+            uses: Vec::new(),
+            consts: Vec::new(),
+            aliases: Vec::new(),
+            types: Vec::new(),
+        };
+
+        let mut errors = Errors::default();
         while !input.is_empty() {
-            let lookahead = input.lookahead1();
-            if lookahead.peek(Token![use]) {
-                let _: Token![use] = input.parse()?;
-                uses.push(Use::parse(input)?);
-                continue;
+            let start = input.cursor();
+            if let Err(err) = scheme.parse_item(input) {
+                errors.push(err);
+                skip_to_scheme_item(input, start);
             }
+        }
+        errors.finish()?;
 
-            if !lookahead.peek(Token![@]) {
-                return Err(lookahead.error());
-            }
+        Ok(scheme)
+    }
+}
 
-            let _: Token![@] = input.parse()?;
-            let _: kw::ver = input.parse()?;
-            let version = MajorVersion::parse(input)?;
+impl Scheme {
+    fn parse_item(&mut self, input: ParseStream) -> Result<()> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(Token![use]) {
+            let _: Token![use] = input.parse()?;
+            self.uses.push(Use::parse(input)?);
+            return Ok(());
+        }
 
-            let type_def = {
-                let lookahead = input.lookahead1();
-                if lookahead.peek(kw::obj) {
-                    let _: kw::obj = input.parse()?;
-                    let object = Object::parse(input, version)?;
-                    Type::Object(object)
-                } else if lookahead.peek(Token![struct]) {
-                    let _: Token![struct] = input.parse()?;
-                    let struct_ = Struct::parse(input, version)?;
-                    Type::Struct(struct_)
-                } else if lookahead.peek(Token![union]) {
-                    let _: Token![union] = input.parse()?;
-                    let union_ = Union::parse(input, version)?;
-                    Type::Union(union_)
-                } else if lookahead.peek(Token![enum]) {
-                    let _: Token![enum] = input.parse()?;
-                    let enum_ = Enum::parse(input, version)?;
-                    Type::Enum(enum_)
-                } else if lookahead.peek(Token![fn]) {
-                    let _: Token![fn] = input.parse()?;
-                    let fn_ = Function::parse(input, version)?;
-                    Type::Function(fn_)
-                } else if lookahead.peek(kw::cmd) {
-                    let _: kw::cmd = input.parse()?;
-                    let cmd_ = Command::parse(input, version)?;
-                    Type::Command(cmd_)
-                } else {
-                    return Err(lookahead.error());
-                }
-            };
+        if lookahead.peek(Token![const]) {
+            let _: Token![const] = input.parse()?;
+            self.consts.push(Const::parse(input)?);
+            return Ok(());
+        }
+
+        if lookahead.peek(Token![type]) {
+            let _: Token![type] = input.parse()?;
+            self.aliases.push(TypeAlias::parse(input)?);
+            return Ok(());
+        }
 
-            types.push(type_def);
+        if !lookahead.peek(Token![@]) {
+            return Err(lookahead.error());
         }
 
-        Ok(Self { name, uses, types })
+        let _: Token![@] = input.parse()?;
+        let _: kw::ver = input.parse()?;
+        let version = MajorVersion::parse(input)?;
+
+        let type_def = {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(kw::obj) {
+                let _: kw::obj = input.parse()?;
+                let object = Object::parse(input, version)?;
+                Type::Object(object)
+            } else if lookahead.peek(Token![struct]) {
+                let _: Token![struct] = input.parse()?;
+                let struct_ = Struct::parse(input, version)?;
+                Type::Struct(struct_)
+            } else if lookahead.peek(Token![union]) {
+                let _: Token![union] = input.parse()?;
+                let union_ = Union::parse(input, version)?;
+                Type::Union(union_)
+            } else if lookahead.peek(Token![enum]) {
+                let _: Token![enum] = input.parse()?;
+                let enum_ = Enum::parse(input, version)?;
+                Type::Enum(enum_)
+            } else if lookahead.peek(kw::flags) {
+                let _: kw::flags = input.parse()?;
+                let flags = Flags::parse(input, version)?;
+                Type::Flags(flags)
+            } else if lookahead.peek(Token![fn]) {
+                let _: Token![fn] = input.parse()?;
+                let fn_ = Function::parse(input, version)?;
+                Type::Function(fn_)
+            } else if lookahead.peek(kw::cmd) {
+                let _: kw::cmd = input.parse()?;
+                let cmd_ = Command::parse(input, version)?;
+                Type::Command(cmd_)
+            } else {
+                return Err(lookahead.error());
+            }
+        };
+
+        self.types.push(type_def);
+        Ok(())
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Use {
-    segments: Vec<String>,
-    alias: Option<String>,
+    pub segments: Vec<String>,
+    pub alias: Option<String>,
 }
 
 impl Use {
+    /// Returns the name the used scheme is referred to by in this scheme.
+    pub fn name(&self) -> &str {
+        self.alias
+            .as_deref()
+            .unwrap_or_else(|| self.segments.last().unwrap())
+    }
+
     fn parse(input: ParseStream) -> Result<Self> {
         let mut segments = Vec::new();
         let mut alias = None;
@@ -128,16 +175,214 @@ impl Use {
     }
 }
 
+#[derive(Debug)]
+pub struct Const {
+    pub name_span: Span,
+    pub name: String,
+    pub const_type: Primitive,
+    pub value: ConstValue,
+}
+
+impl Const {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name_ident: Ident = input.parse()?;
+        let _: Token![:] = input.parse()?;
+
+        let const_type = match FieldType::parse(input)? {
+            FieldType::Primitive(_, primitive) if primitive.is_const_type() => primitive,
+            field_type => {
+                return Err(Error::new(
+                    field_type.span(),
+                    "constant type must be an integer, float, bool, char or str primitive",
+                ))
+            }
+        };
+
+        let _: Token![=] = input.parse()?;
+        let value = ConstValue::parse(input, &const_type)?;
+        let _: Token![;] = input.parse()?;
+
+        Ok(Self {
+            name_span: name_ident.span(),
+            name: name_ident.to_string(),
+            const_type,
+            value,
+        })
+    }
+}
+
+impl PartialEq<Const> for Const {
+    fn eq(&self, other: &Const) -> bool {
+        self.name == other.name && self.const_type == other.const_type && self.value == other.value
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConstValue {
+    Int(i128),
+    /// A `u128` value above `i128::MAX`.
+    UInt(u128),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    String(String),
+}
+
+impl ConstValue {
+    fn parse(input: ParseStream, const_type: &Primitive) -> Result<Self> {
+        let span = input.span();
+        if let Primitive::UInt128 = const_type {
+            let int_lit: LitInt = input.parse()?;
+            return match int_lit.base10_parse::<u128>() {
+                Ok(value) => Ok(i128::try_from(value).map_or(Self::UInt(value), Self::Int)),
+                Err(_) => Err(Error::new(
+                    int_lit.span(),
+                    "constant value is out of range for its type",
+                )),
+            };
+        }
+        if let Some((min, max)) = const_type.int_range() {
+            let int_lit: LitInt = input.parse()?;
+            match int_lit.base10_parse::<i128>() {
+                Ok(value) if value >= min && value <= max => Ok(Self::Int(value)),
+                _ => Err(Error::new(
+                    int_lit.span(),
+                    "constant value is out of range for its type",
+                )),
+            }
+        } else {
+            match const_type {
+                Primitive::Float32 | Primitive::Float64 => {
+                    let lookahead = input.lookahead1();
+                    let (span, value) = if lookahead.peek(LitFloat) {
+                        let float_lit: LitFloat = input.parse()?;
+                        (float_lit.span(), float_lit.base10_parse::<f64>()?)
+                    } else if lookahead.peek(LitInt) {
+                        let int_lit: LitInt = input.parse()?;
+                        (int_lit.span(), int_lit.base10_parse::<f64>()?)
+                    } else {
+                        return Err(lookahead.error());
+                    };
+                    // Values too large for the type parse as infinite, which
+                    // cannot be written back as a literal.
+                    let finite = match const_type {
+                        Primitive::Float32 => (value as f32).is_finite(),
+                        _ => value.is_finite(),
+                    };
+                    if !finite {
+                        return Err(Error::new(
+                            span,
+                            "constant value is out of range for its type",
+                        ));
+                    }
+                    Ok(Self::Float(value))
+                }
+                Primitive::Boolean => Ok(Self::Bool(input.parse::<LitBool>()?.value)),
+                Primitive::Char => Ok(Self::Char(input.parse::<LitChar>()?.value())),
+                Primitive::String => Ok(Self::String(input.parse::<LitStr>()?.value())),
+                _ => Err(Error::new(span, "unsupported constant type")),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TypeAlias {
+    pub name_span: Span,
+    pub name: String,
+    pub field_type: FieldType,
+}
+
+impl TypeAlias {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let name_ident: Ident = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let field_type = FieldType::parse(input)?;
+        let _: Token![;] = input.parse()?;
+
+        Ok(Self {
+            name_span: name_ident.span(),
+            name: name_ident.to_string(),
+            field_type,
+        })
+    }
+}
+
+impl PartialEq<TypeAlias> for TypeAlias {
+    fn eq(&self, other: &TypeAlias) -> bool {
+        self.name == other.name && self.field_type == other.field_type
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Type {
     Object(Object),
     Struct(Struct),
     Union(Union),
     Enum(Enum),
+    Flags(Flags),
     Function(Function),
     Command(Command),
 }
 
+impl Type {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Object(Object::Struct(Struct { name, .. }))
+            | Self::Object(Object::Union(Union { name, .. }))
+            | Self::Object(Object::Enum(Enum { name, .. }))
+            | Self::Struct(Struct { name, .. })
+            | Self::Union(Union { name, .. })
+            | Self::Enum(Enum { name, .. })
+            | Self::Flags(Flags { name, .. })
+            | Self::Function(Function { name, .. })
+            | Self::Command(Command { name, .. }) => name,
+        }
+    }
+
+    pub fn name_span(&self) -> Span {
+        match self {
+            Self::Object(Object::Struct(Struct { name_span, .. }))
+            | Self::Object(Object::Union(Union { name_span, .. }))
+            | Self::Object(Object::Enum(Enum { name_span, .. }))
+            | Self::Struct(Struct { name_span, .. })
+            | Self::Union(Union { name_span, .. })
+            | Self::Enum(Enum { name_span, .. })
+            | Self::Flags(Flags { name_span, .. })
+            | Self::Function(Function { name_span, .. })
+            | Self::Command(Command { name_span, .. }) => *name_span,
+        }
+    }
+
+    pub fn version(&self) -> &MajorVersion {
+        match self {
+            Self::Object(Object::Struct(Struct { version, .. }))
+            | Self::Object(Object::Union(Union { version, .. }))
+            | Self::Object(Object::Enum(Enum { version, .. }))
+            | Self::Struct(Struct { version, .. })
+            | Self::Union(Union { version, .. })
+            | Self::Enum(Enum { version, .. })
+            | Self::Flags(Flags { version, .. })
+            | Self::Function(Function { version, .. })
+            | Self::Command(Command { version, .. }) => version,
+        }
+    }
+
+    pub fn generics(&self) -> &[GenericParam] {
+        match self {
+            Self::Object(Object::Struct(Struct { generics, .. }))
+            | Self::Object(Object::Union(Union { generics, .. }))
+            | Self::Struct(Struct { generics, .. })
+            | Self::Union(Union { generics, .. }) => generics,
+            Self::Object(Object::Enum(_))
+            | Self::Enum(_)
+            | Self::Flags(_)
+            | Self::Function(_)
+            | Self::Command(_) => &[],
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Object {
     Struct(Struct),
@@ -152,10 +397,13 @@ impl Object {
 
         let lookahead = kind_input.lookahead1();
         if lookahead.peek(Token![struct]) {
+            let _: Token![struct] = kind_input.parse()?;
             Ok(Object::Struct(Struct::parse(input, version)?))
         } else if lookahead.peek(Token![union]) {
+            let _: Token![union] = kind_input.parse()?;
             Ok(Object::Union(Union::parse(input, version)?))
         } else if lookahead.peek(Token![enum]) {
+            let _: Token![enum] = kind_input.parse()?;
             Ok(Object::Enum(Enum::parse(input, version)?))
         } else {
             Err(lookahead.error())
@@ -168,6 +416,7 @@ pub struct Struct {
     pub version: MajorVersion,
     pub name_span: Span,
     pub name: String,
+    pub generics: Vec<GenericParam>,
     pub body: StructBody,
 }
 
@@ -178,6 +427,7 @@ impl Struct {
             version,
             name_span: name_ident.span(),
             name: name_ident.to_string(),
+            generics: GenericParam::parse_all(input)?,
             body: StructBody::parse(input, false)?,
         })
     }
@@ -185,7 +435,10 @@ impl Struct {
 
 impl PartialEq<Struct> for Struct {
     fn eq(&self, other: &Struct) -> bool {
-        self.version == other.version && self.name == other.name && self.body == other.body
+        self.version == other.version
+            && self.name == other.name
+            && self.generics == other.generics
+            && self.body == other.body
     }
 }
 
@@ -194,32 +447,35 @@ pub struct Union {
     pub version: MajorVersion,
     pub name_span: Span,
     pub name: String,
+    pub generics: Vec<GenericParam>,
     pub items: Vec<UnionItem>,
 }
 
 impl Union {
     fn parse(input: ParseStream, version: MajorVersion) -> Result<Self> {
         let name_ident: Ident = input.parse()?;
+        let generics = GenericParam::parse_all(input)?;
 
         let items_input;
         braced!(items_input in input);
         let input = &items_input;
 
-        let mut items = Vec::new();
-        while !input.is_empty() {
+        let items = parse_comma_items(input, |input| {
             let (include, version) = parse_include_or_minor_version(input, false, "union fields")?;
-            if include.is_some() {
-                items.push(UnionItem::Include(include.unwrap()));
+            let item = if let Some(include) = include {
+                UnionItem::Include(include)
             } else {
-                items.push(UnionItem::Field(UnionField::parse(input, version)?));
-            }
+                UnionItem::Field(UnionField::parse(input, version)?)
+            };
             let _: Token![,] = input.parse()?;
-        }
+            Ok(item)
+        })?;
 
         Ok(Self {
             version,
             name_span: name_ident.span(),
             name: name_ident.to_string(),
+            generics,
             items,
         })
     }
@@ -227,7 +483,51 @@ impl Union {
 
 impl PartialEq<Union> for Union {
     fn eq(&self, other: &Union) -> bool {
-        self.version == other.version && self.name == other.name && self.items == other.items
+        self.version == other.version
+            && self.name == other.name
+            && self.generics == other.generics
+            && self.items == other.items
+    }
+}
+
+#[derive(Debug)]
+pub struct GenericParam {
+    pub name_span: Span,
+    pub name: String,
+}
+
+impl GenericParam {
+    fn parse_all(input: ParseStream) -> Result<Vec<Self>> {
+        let mut params = Vec::new();
+        if !input.peek(Token![<]) {
+            return Ok(params);
+        }
+
+        let _: Token![<] = input.parse()?;
+        while !input.peek(Token![>]) {
+            let name_ident: Ident = input.parse()?;
+            params.push(Self {
+                name_span: name_ident.span(),
+                name: name_ident.to_string(),
+            });
+
+            if !input.peek(Token![>]) {
+                let _: Token![,] = input.parse()?;
+            }
+        }
+        let _: Token![>] = input.parse()?;
+
+        if params.is_empty() {
+            return Err(input.error("expected at least one generic parameter"));
+        }
+
+        Ok(params)
+    }
+}
+
+impl PartialEq<GenericParam> for GenericParam {
+    fn eq(&self, other: &GenericParam) -> bool {
+        self.name == other.name
     }
 }
 
@@ -313,19 +613,16 @@ pub enum StructItem {
 
 impl StructItem {
     fn parse_all(input: ParseStream, invalid_for: &str) -> Result<Vec<Self>> {
-        let mut items = Vec::new();
-
-        while !input.is_empty() {
+        parse_comma_items(input, |input| {
             let (include, version) = parse_include_or_minor_version(input, true, invalid_for)?;
-            if include.is_some() {
-                items.push(StructItem::Include(include.unwrap()));
+            let item = if let Some(include) = include {
+                StructItem::Include(include)
             } else {
-                items.push(StructItem::Field(StructField::parse(input, version)?));
-            }
+                StructItem::Field(StructField::parse(input, version)?)
+            };
             let _: Token![,] = input.parse()?;
-        }
-
-        Ok(items)
+            Ok(item)
+        })
     }
 }
 
@@ -365,7 +662,7 @@ impl PartialEq<StructField> for StructField {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct Tuple(Vec<TupleItem>);
+pub struct Tuple(pub Vec<TupleItem>);
 
 impl Tuple {
     fn parse(input: ParseStream) -> Result<Self> {
@@ -373,22 +670,22 @@ impl Tuple {
         parenthesized!(items_input in input);
         let input = &items_input;
 
-        let mut items = Vec::new();
-        while !input.is_empty() {
+        let items = parse_comma_items(input, |input| {
             let (include, version) = parse_include_or_minor_version(input, true, "tuple fields")?;
-            if include.is_some() {
-                items.push(TupleItem::Include(include.unwrap()));
+            let item = if let Some(include) = include {
+                TupleItem::Include(include)
             } else {
-                items.push(TupleItem::Field(TupleField {
+                TupleItem::Field(TupleField {
                     version,
                     field_type: FieldType::parse(input)?,
-                }));
-            }
+                })
+            };
 
             if !input.is_empty() {
                 let _: Token![,] = input.parse()?;
             }
-        }
+            Ok(item)
+        })?;
 
         Ok(Self(items))
     }
@@ -422,16 +719,16 @@ impl Enum {
         braced!(items_input in input);
         let input = &items_input;
 
-        let mut items = Vec::new();
-        while !input.is_empty() {
+        let items = parse_comma_items(input, |input| {
             let (include, version) = parse_include_or_minor_version(input, false, "enum fields")?;
-            if include.is_some() {
-                items.push(EnumItem::Include(include.unwrap()));
+            let item = if let Some(include) = include {
+                EnumItem::Include(include)
             } else {
-                items.push(EnumItem::Field(EnumField::parse(input, version)?));
-            }
+                EnumItem::Field(EnumField::parse(input, version)?)
+            };
             let _: Token![,] = input.parse()?;
-        }
+            Ok(item)
+        })?;
 
         Ok(Self {
             version,
@@ -490,6 +787,122 @@ impl PartialEq<EnumField> for EnumField {
     }
 }
 
+#[derive(Debug)]
+pub struct Flags {
+    pub version: MajorVersion,
+    pub name_span: Span,
+    pub name: String,
+    pub items: Vec<FlagsItem>,
+}
+
+impl Flags {
+    fn parse(input: ParseStream, version: MajorVersion) -> Result<Self> {
+        let name_ident: Ident = input.parse()?;
+
+        let items_input;
+        braced!(items_input in input);
+        let items = parse_comma_items(&items_input, |input| {
+            let item = if input.peek(Token![@]) && input.peek2(kw::ver) {
+                let _: Token![@] = input.parse()?;
+                let _: kw::ver = input.parse()?;
+                FlagsItem::Include(Include::parse(input)?)
+            } else {
+                FlagsItem::Field(FlagsField::parse(input)?)
+            };
+            let _: Token![,] = input.parse()?;
+            Ok(item)
+        })?;
+
+        Ok(Self {
+            version,
+            name_span: name_ident.span(),
+            name: name_ident.to_string(),
+            items,
+        })
+    }
+}
+
+impl PartialEq<Flags> for Flags {
+    fn eq(&self, other: &Flags) -> bool {
+        self.version == other.version && self.name == other.name && self.items == other.items
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FlagsItem {
+    Include(Include),
+    Field(FlagsField),
+}
+
+#[derive(Debug)]
+pub struct FlagsField {
+    pub added: Option<MinorVersion>,
+    pub removed: Option<MinorVersion>,
+    pub name_span: Span,
+    pub name: String,
+    pub bit_span: Span,
+    pub bit: u32,
+}
+
+impl FlagsField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut added = None;
+        let mut removed = None;
+        while input.peek(Token![@]) {
+            let _: Token![@] = input.parse()?;
+
+            let lookahead = input.lookahead1();
+            let (directive, version) = if lookahead.peek(kw::add) {
+                let _: kw::add = input.parse()?;
+                ("@add", &mut added)
+            } else if lookahead.peek(kw::rem) {
+                let _: kw::rem = input.parse()?;
+                ("@rem", &mut removed)
+            } else {
+                return Err(lookahead.error());
+            };
+
+            if version.is_some() {
+                return Err(input.error(format!("duplicate {} directive", directive)));
+            }
+            *version = Some(MinorVersion::parse(input)?);
+        }
+
+        if !input.peek(Ident::peek_any) {
+            return Err(input.error("expected flags field name"));
+        }
+
+        let name_ident: Ident = input.parse()?;
+        let _: Token![=] = input.parse()?;
+        let bit_lit: LitInt = input.parse()?;
+        let bit = parse_int_lit(&bit_lit, "invalid flag bit literal")?;
+        if bit >= 64 {
+            return Err(Error::new(
+                bit_lit.span(),
+                "flag bit position must be less than 64",
+            ));
+        }
+
+        Ok(Self {
+            added,
+            removed,
+            name_span: name_ident.span(),
+            name: name_ident.to_string(),
+            bit_span: bit_lit.span(),
+            bit,
+        })
+    }
+}
+
+impl PartialEq<FlagsField> for FlagsField {
+    fn eq(&self, other: &FlagsField) -> bool {
+        self.added == other.added
+            && self.removed == other.removed
+            && self.name == other.name
+            && self.bit == other.bit
+    }
+}
+
 #[derive(Debug)]
 pub struct Function {
     pub version: MajorVersion,
@@ -629,20 +1042,19 @@ impl Include {
         if input.peek(Brace) {
             let items_input;
             braced!(items_input in input);
-            let input = &items_input;
 
-            while !input.is_empty() {
+            items = parse_comma_items(&items_input, |input| {
                 let _: Token![@] = input.parse()?;
 
                 let lookahead = input.lookahead1();
-                if lookahead.peek(kw::add) {
+                let item = if lookahead.peek(kw::add) {
                     let _: kw::add = input.parse()?;
 
                     let ident_input;
                     parenthesized!(ident_input in input);
                     let ident: Ident = ident_input.parse()?;
 
-                    items.push(IncludeItem::Add(ident.to_string()));
+                    IncludeItem::Add(ident.to_string())
                 } else if lookahead.peek(kw::rem) {
                     let _: kw::rem = input.parse()?;
 
@@ -650,11 +1062,14 @@ impl Include {
                     parenthesized!(ident_input in input);
                     let ident: Ident = ident_input.parse()?;
 
-                    items.push(IncludeItem::Rem(ident.to_string()));
-                }
+                    IncludeItem::Rem(ident.to_string())
+                } else {
+                    return Err(lookahead.error());
+                };
 
                 let _: Token![,] = input.parse()?;
-            }
+                Ok(item)
+            })?;
         }
 
         Ok(Self { version, items })
@@ -668,7 +1083,7 @@ pub enum IncludeItem {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct MajorVersion(u16);
+pub struct MajorVersion(pub u16);
 
 impl MajorVersion {
     fn parse(input: ParseStream) -> Result<Self> {
@@ -678,8 +1093,11 @@ impl MajorVersion {
 
         let int_lit: LitInt = input.parse()?;
         let value = int_lit.to_string();
-        let Ok(major) = u16::from_str_radix(&value, 10) else {
-            return Err(Error::new(int_lit.span(), "major version must be valid u16 value"));
+        let Ok(major) = value.parse::<u16>() else {
+            return Err(Error::new(
+                int_lit.span(),
+                "major version must be valid u16 value",
+            ));
         };
 
         Ok(Self(major))
@@ -687,7 +1105,7 @@ impl MajorVersion {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct MinorVersion(u16, u16);
+pub struct MinorVersion(pub u16, pub u16);
 
 impl MinorVersion {
     fn parse(input: ParseStream) -> Result<Self> {
@@ -700,7 +1118,7 @@ impl MinorVersion {
         let (major, minor) = value.split_once('.').unwrap();
 
         let parse_version_num = |num: &str| -> Result<u16> {
-            match u16::from_str_radix(num, 10) {
+            match num.parse::<u16>() {
                 Ok(num) => Ok(num),
                 Err(_) => Err(Error::new(
                     float_lit.span(),
@@ -718,38 +1136,147 @@ impl MinorVersion {
 #[derive(Debug)]
 pub enum FieldType {
     Primitive(Span, Primitive),
-    Type(Span, String, Option<String>, Option<MajorVersion>),
+    Type(
+        Span,
+        String,
+        Option<String>,
+        Vec<FieldType>,
+        Option<MajorVersion>,
+    ),
     Optional(Span, Box<FieldType>),
     Reference(Span, Box<FieldType>),
-    Array(Span, Box<FieldType>, u32),
+    Array(Span, Box<FieldType>, ArraySize),
     List(Span, Box<FieldType>),
-    Map(Span, Box<FieldType>, Box<FieldType>),
+    Map(Span, Box<FieldType>, Box<FieldType>, Collection),
+    Set(Span, Box<FieldType>, Collection),
     Tuple(Span, Tuple),
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum Collection {
+    Hashed,
+    Ordered,
+}
+
+impl Collection {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![;]) {
+            let _: Token![;] = input.parse()?;
+            let _: kw::ord = input.parse()?;
+            Ok(Self::Ordered)
+        } else {
+            Ok(Self::Hashed)
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum Primitive {
     Int8,
     Int16,
     Int32,
     Int64,
+    Int128,
 
     UInt8,
     UInt16,
     UInt32,
     UInt64,
+    UInt128,
 
     Float32,
     Float64,
 
     Boolean,
+    Char,
     String,
     Bytes,
 
+    Timestamp,
+    Duration,
+    Uuid,
+    Decimal,
+
     Any,
 }
 
+impl Primitive {
+    pub fn int_range(&self) -> Option<(i128, i128)> {
+        match self {
+            Self::Int8 => Some((i8::MIN as i128, i8::MAX as i128)),
+            Self::Int16 => Some((i16::MIN as i128, i16::MAX as i128)),
+            Self::Int32 => Some((i32::MIN as i128, i32::MAX as i128)),
+            Self::Int64 => Some((i64::MIN as i128, i64::MAX as i128)),
+            Self::Int128 => Some((i128::MIN, i128::MAX)),
+            Self::UInt8 => Some((0, u8::MAX as i128)),
+            Self::UInt16 => Some((0, u16::MAX as i128)),
+            Self::UInt32 => Some((0, u32::MAX as i128)),
+            Self::UInt64 => Some((0, u64::MAX as i128)),
+            // Values above i128::MAX are held as `ConstValue::UInt` and
+            // `Value::UInt`.
+            Self::UInt128 => Some((0, i128::MAX)),
+            _ => None,
+        }
+    }
+
+    pub fn is_const_type(&self) -> bool {
+        self.int_range().is_some()
+            || matches!(
+                self,
+                Self::Float32 | Self::Float64 | Self::Boolean | Self::Char | Self::String
+            )
+    }
+}
+
+#[derive(Debug)]
+pub enum ArraySize {
+    Value(u32),
+    Const(Span, String),
+}
+
+impl ArraySize {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(LitInt) {
+            let size: LitInt = input.parse()?;
+            Ok(Self::Value(parse_int_lit(
+                &size,
+                "invalid array size literal",
+            )?))
+        } else if lookahead.peek(Ident) {
+            let ident: Ident = input.parse()?;
+            Ok(Self::Const(ident.span(), ident.to_string()))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+impl PartialEq<ArraySize> for ArraySize {
+    fn eq(&self, other: &ArraySize) -> bool {
+        match (self, other) {
+            (Self::Value(size), Self::Value(other_size)) => size == other_size,
+            (Self::Const(_, name), Self::Const(_, other_name)) => name == other_name,
+            _ => false,
+        }
+    }
+}
+
 impl FieldType {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Primitive(span, _)
+            | Self::Type(span, ..)
+            | Self::Optional(span, _)
+            | Self::Reference(span, _)
+            | Self::Array(span, ..)
+            | Self::List(span, _)
+            | Self::Map(span, ..)
+            | Self::Set(span, ..)
+            | Self::Tuple(span, _) => *span,
+        }
+    }
+
     fn parse(input: ParseStream) -> Result<Self> {
         let lookahead = input.lookahead1();
         if lookahead.peek(Ident::peek_any) {
@@ -761,20 +1288,26 @@ impl FieldType {
                 "i16" => Ok(FieldType::Primitive(span, Primitive::Int16)),
                 "i32" => Ok(FieldType::Primitive(span, Primitive::Int32)),
                 "i64" => Ok(FieldType::Primitive(span, Primitive::Int64)),
+                "i128" => Ok(FieldType::Primitive(span, Primitive::Int128)),
                 "u8" => Ok(FieldType::Primitive(span, Primitive::UInt8)),
                 "u16" => Ok(FieldType::Primitive(span, Primitive::UInt16)),
                 "u32" => Ok(FieldType::Primitive(span, Primitive::UInt32)),
                 "u64" => Ok(FieldType::Primitive(span, Primitive::UInt64)),
+                "u128" => Ok(FieldType::Primitive(span, Primitive::UInt128)),
                 "f32" => Ok(FieldType::Primitive(span, Primitive::Float32)),
                 "f64" => Ok(FieldType::Primitive(span, Primitive::Float64)),
                 "bool" => Ok(FieldType::Primitive(span, Primitive::Boolean)),
+                "char" => Ok(FieldType::Primitive(span, Primitive::Char)),
                 "str" => Ok(FieldType::Primitive(span, Primitive::String)),
                 "bytes" => Ok(FieldType::Primitive(span, Primitive::Bytes)),
+                "timestamp" => Ok(FieldType::Primitive(span, Primitive::Timestamp)),
+                "duration" => Ok(FieldType::Primitive(span, Primitive::Duration)),
+                "uuid" => Ok(FieldType::Primitive(span, Primitive::Uuid)),
+                "decimal" => Ok(FieldType::Primitive(span, Primitive::Decimal)),
                 "any" => Ok(FieldType::Primitive(span, Primitive::Any)),
                 _ => {
-                    let second_ident = if input.peek(Token![:]) {
-                        let _: Token![:] = input.parse()?;
-                        let _: Token![:] = input.parse()?;
+                    let second_ident = if input.peek(Token![::]) {
+                        let _: Token![::] = input.parse()?;
 
                         let ident: Ident = input.parse()?;
                         Some(ident.to_string())
@@ -782,6 +1315,18 @@ impl FieldType {
                         None
                     };
 
+                    let mut generic_args = Vec::new();
+                    if input.peek(Token![<]) {
+                        let _: Token![<] = input.parse()?;
+                        while !input.peek(Token![>]) {
+                            generic_args.push(Self::parse(input)?);
+                            if !input.peek(Token![>]) {
+                                let _: Token![,] = input.parse()?;
+                            }
+                        }
+                        let _: Token![>] = input.parse()?;
+                    }
+
                     let version = if input.peek(Token![@]) {
                         let _: Token![@] = input.parse()?;
                         let _: kw::ver = input.parse()?;
@@ -790,7 +1335,7 @@ impl FieldType {
                         None
                     };
 
-                    Ok(Self::Type(span, ident, second_ident, version))
+                    Ok(Self::Type(span, ident, second_ident, generic_args, version))
                 }
             }
         } else if lookahead.peek(Token![?]) {
@@ -811,25 +1356,23 @@ impl FieldType {
                 let lookahead = input.lookahead1();
                 if lookahead.peek(Token![;]) {
                     let _: Token![;] = input.parse()?;
-                    let size: LitInt = input.parse()?;
+                    let size = ArraySize::parse(input)?;
                     expect_empty(input)?;
 
-                    Ok(Self::Array(
-                        bracket_span,
-                        Box::new(element_type),
-                        parse_int_lit(&size, "invalid array size literal")?,
-                    ))
+                    Ok(Self::Array(bracket_span, Box::new(element_type), size))
                 } else if lookahead.peek(Token![:]) {
                     let key_type = element_type;
 
                     let _: Token![:] = input.parse()?;
                     let value_type = Self::parse(input)?;
+                    let collection = Collection::parse(input)?;
                     expect_empty(input)?;
 
                     Ok(Self::Map(
                         bracket_span,
                         Box::new(key_type),
                         Box::new(value_type),
+                        collection,
                     ))
                 } else {
                     Err(lookahead.error())
@@ -837,6 +1380,18 @@ impl FieldType {
             } else {
                 Ok(Self::List(bracket_span, Box::new(element_type)))
             }
+        } else if lookahead.peek(Brace) {
+            let brace_span = input.span();
+
+            let braced_input;
+            braced!(braced_input in input);
+            let input = &braced_input;
+
+            let element_type = Self::parse(input)?;
+            let collection = Collection::parse(input)?;
+            expect_empty(input)?;
+
+            Ok(Self::Set(brace_span, Box::new(element_type), collection))
         } else if lookahead.peek(Paren) {
             let paren_span = input.span();
             let tuple = Tuple::parse(input)?;
@@ -857,10 +1412,18 @@ impl PartialEq<FieldType> for FieldType {
                     false
                 }
             }
-            Self::Type(_, ident, extra_ident, version) => {
-                if let Self::Type(_, other_ident, other_extra_ident, other_version) = other {
+            Self::Type(_, ident, extra_ident, generic_args, version) => {
+                if let Self::Type(
+                    _,
+                    other_ident,
+                    other_extra_ident,
+                    other_generic_args,
+                    other_version,
+                ) = other
+                {
                     ident == other_ident
                         && extra_ident == other_extra_ident
+                        && generic_args == other_generic_args
                         && version == other_version
                 } else {
                     false
@@ -894,9 +1457,18 @@ impl PartialEq<FieldType> for FieldType {
                     false
                 }
             }
-            Self::Map(_, key_type, value_type) => {
-                if let Self::Map(_, other_key_type, other_value_type) = other {
-                    key_type == other_key_type && value_type == other_value_type
+            Self::Map(_, key_type, value_type, collection) => {
+                if let Self::Map(_, other_key_type, other_value_type, other_collection) = other {
+                    key_type == other_key_type
+                        && value_type == other_value_type
+                        && collection == other_collection
+                } else {
+                    false
+                }
+            }
+            Self::Set(_, element_type, collection) => {
+                if let Self::Set(_, other_element_type, other_collection) = other {
+                    element_type == other_element_type && collection == other_collection
                 } else {
                     false
                 }
@@ -912,8 +1484,91 @@ impl PartialEq<FieldType> for FieldType {
     }
 }
 
+/// Accumulates errors so that parsing and validation can report every problem
+/// in a scheme at once instead of stopping at the first one.
+#[derive(Default)]
+pub struct Errors(Option<Error>);
+
+impl Errors {
+    pub fn push(&mut self, err: Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(err),
+            None => self.0 = Some(err),
+        }
+    }
+
+    pub fn check(&mut self, result: Result<()>) {
+        if let Err(err) = result {
+            self.push(err);
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self.0 {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses comma terminated items until `input` is exhausted, recovering from
+/// an invalid item by skipping past the next `,`.
+fn parse_comma_items<T>(
+    input: ParseStream,
+    mut parse_item: impl FnMut(ParseStream) -> Result<T>,
+) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+    let mut errors = Errors::default();
+    while !input.is_empty() {
+        match parse_item(input) {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                errors.push(err);
+                while !input.is_empty() {
+                    if input.parse::<Option<Token![,]>>().unwrap().is_some() {
+                        break;
+                    }
+                    let _: TokenTree = input.parse().unwrap();
+                }
+            }
+        }
+    }
+    errors.finish()?;
+    Ok(items)
+}
+
+/// Skips tokens until the start of the next `use`, `const`, `type` or `@ver`
+/// item, always making progress past `start`.
+fn skip_to_scheme_item(input: ParseStream, start: Cursor) {
+    while !input.is_empty() {
+        if input.cursor() != start && peek_scheme_item(input) {
+            break;
+        }
+        let _: TokenTree = input.parse().unwrap();
+    }
+}
+
+fn peek_scheme_item(input: ParseStream) -> bool {
+    if input.peek(Token![use]) || input.peek(Token![const]) || input.peek(Token![type]) {
+        return true;
+    }
+
+    let fork = input.fork();
+    fork.parse::<Token![@]>().is_ok()
+        && fork.parse::<kw::ver>().is_ok()
+        && fork.peek(Paren)
+        && fork.parse::<TokenTree>().is_ok()
+        && (fork.peek(kw::obj)
+            || fork.peek(Token![struct])
+            || fork.peek(Token![union])
+            || fork.peek(Token![enum])
+            || fork.peek(kw::flags)
+            || fork.peek(Token![fn])
+            || fork.peek(kw::cmd))
+}
+
 fn parse_int_lit(int_lit: &LitInt, msg: &str) -> Result<u32> {
-    match u32::from_str_radix(&int_lit.to_string(), 10) {
+    match int_lit.to_string().parse::<u32>() {
         Ok(int) => Ok(int),
         Err(_) => Err(Error::new(int_lit.span(), msg)),
     }
@@ -932,6 +1587,14 @@ mod tests {
     use super::*;
     use quote::quote;
 
+    /// Returns the messages of the errors parsing `tokens` reports.
+    fn parse_errors(tokens: proc_macro2::TokenStream) -> Vec<String> {
+        match syn::parse2::<Scheme>(tokens) {
+            Ok(_) => Vec::new(),
+            Err(err) => err.into_iter().map(|err| err.to_string()).collect(),
+        }
+    }
+
     #[test]
     fn all_valid_input() {
         let scheme: Scheme = syn::parse2(quote! {
@@ -1119,11 +1782,14 @@ mod tests {
                         alias: None,
                     },
                 ],
+                consts: Vec::new(),
+                aliases: Vec::new(),
                 types: vec![
                     Type::Struct(Struct {
                         version: MajorVersion(1),
                         name_span: dummy_span,
                         name: "Struct".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Items(vec![
                             StructItem::Field(StructField {
                                 version: None,
@@ -1213,7 +1879,7 @@ mod tests {
                                 field_type: FieldType::Array(
                                     dummy_span,
                                     Box::new(FieldType::Primitive(dummy_span, Primitive::UInt8)),
-                                    32
+                                    ArraySize::Value(32)
                                 ),
                             }),
                             StructItem::Field(StructField {
@@ -1233,6 +1899,7 @@ mod tests {
                                     dummy_span,
                                     Box::new(FieldType::Primitive(dummy_span, Primitive::UInt8)),
                                     Box::new(FieldType::Primitive(dummy_span, Primitive::UInt8)),
+                                    Collection::Hashed,
                                 ),
                             }),
                             StructItem::Field(StructField {
@@ -1273,6 +1940,7 @@ mod tests {
                                     dummy_span,
                                     "types".to_string(),
                                     Some("Struct".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(1))
                                 ),
                             }),
@@ -1286,6 +1954,7 @@ mod tests {
                                         dummy_span,
                                         "types".to_string(),
                                         Some("Object".to_string()),
+                                        Vec::new(),
                                         None,
                                     )),
                                 ),
@@ -1296,6 +1965,7 @@ mod tests {
                         version: MajorVersion(2),
                         name_span: dummy_span,
                         name: "Struct".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Items(vec![
                             StructItem::Include(Include {
                                 version: MajorVersion(1),
@@ -1311,7 +1981,7 @@ mod tests {
                                 field_type: FieldType::Array(
                                     dummy_span,
                                     Box::new(FieldType::Primitive(dummy_span, Primitive::UInt16)),
-                                    32,
+                                    ArraySize::Value(32),
                                 ),
                             }),
                             StructItem::Field(StructField {
@@ -1322,6 +1992,7 @@ mod tests {
                                     dummy_span,
                                     Box::new(FieldType::Primitive(dummy_span, Primitive::UInt16)),
                                     Box::new(FieldType::Primitive(dummy_span, Primitive::UInt16)),
+                                    Collection::Hashed,
                                 ),
                             }),
                             StructItem::Field(StructField {
@@ -1356,6 +2027,7 @@ mod tests {
                                     dummy_span,
                                     "extern_scheme".to_string(),
                                     Some("Struct".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(2)),
                                 ),
                             }),
@@ -1365,12 +2037,14 @@ mod tests {
                         version: MajorVersion(1),
                         name_span: dummy_span,
                         name: "NewTypeStruct".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Tuple(Tuple(vec![TupleItem::Field(TupleField {
                             version: None,
                             field_type: FieldType::Type(
                                 dummy_span,
                                 "Struct".to_string(),
                                 None,
+                                Vec::new(),
                                 Some(MajorVersion(1))
                             ),
                         }),])),
@@ -1379,12 +2053,14 @@ mod tests {
                         version: MajorVersion(2),
                         name_span: dummy_span,
                         name: "NewTypeStruct".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Tuple(Tuple(vec![TupleItem::Field(TupleField {
                             version: None,
                             field_type: FieldType::Type(
                                 dummy_span,
                                 "Struct".to_string(),
                                 None,
+                                Vec::new(),
                                 Some(MajorVersion(2))
                             ),
                         }),])),
@@ -1393,6 +2069,7 @@ mod tests {
                         version: MajorVersion(1),
                         name_span: dummy_span,
                         name: "TupleStruct".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Tuple(Tuple(vec![
                             TupleItem::Field(TupleField {
                                 version: None,
@@ -1400,6 +2077,7 @@ mod tests {
                                     dummy_span,
                                     "types".to_string(),
                                     Some("Struct".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(2))
                                 ),
                             }),
@@ -1409,6 +2087,7 @@ mod tests {
                                     dummy_span,
                                     "super_scheme".to_string(),
                                     Some("Enum".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(1))
                                 ),
                             }),
@@ -1418,6 +2097,7 @@ mod tests {
                                     dummy_span,
                                     "types".to_string(),
                                     Some("Union".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(1))
                                 ),
                             }),
@@ -1427,6 +2107,7 @@ mod tests {
                         version: MajorVersion(2),
                         name_span: dummy_span,
                         name: "TupleStruct".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Tuple(Tuple(vec![
                             TupleItem::Field(TupleField {
                                 version: None,
@@ -1434,6 +2115,7 @@ mod tests {
                                     dummy_span,
                                     "types".to_string(),
                                     Some("Struct".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(3))
                                 ),
                             }),
@@ -1443,6 +2125,7 @@ mod tests {
                                     dummy_span,
                                     "super_scheme".to_string(),
                                     Some("Enum".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(2))
                                 ),
                             }),
@@ -1452,6 +2135,7 @@ mod tests {
                                     dummy_span,
                                     "types".to_string(),
                                     Some("Union".to_string()),
+                                    Vec::new(),
                                     Some(MajorVersion(2))
                                 ),
                             }),
@@ -1523,6 +2207,7 @@ mod tests {
                         version: MajorVersion(1),
                         name_span: dummy_span,
                         name: "Union".to_string(),
+                        generics: Vec::new(),
                         items: vec![
                             UnionItem::Field(UnionField {
                                 version: None,
@@ -1579,6 +2264,7 @@ mod tests {
                                             dummy_span,
                                             "NewTypeStruct".to_string(),
                                             None,
+                                            Vec::new(),
                                             Some(MajorVersion(1)),
                                         ),
                                     }),
@@ -1590,6 +2276,7 @@ mod tests {
                                             dummy_span,
                                             "TupleStruct".to_string(),
                                             None,
+                                            Vec::new(),
                                             Some(MajorVersion(1)),
                                         ),
                                     }),
@@ -1601,6 +2288,7 @@ mod tests {
                                             dummy_span,
                                             "extern_scheme".to_string(),
                                             Some("Union".to_string()),
+                                            Vec::new(),
                                             Some(MajorVersion(1)),
                                         ),
                                     }),
@@ -1618,6 +2306,7 @@ mod tests {
                         version: MajorVersion(2),
                         name_span: dummy_span,
                         name: "Union".to_string(),
+                        generics: Vec::new(),
                         items: vec![
                             UnionItem::Include(Include {
                                 version: MajorVersion(1),
@@ -1635,6 +2324,7 @@ mod tests {
                         version: MajorVersion(1),
                         name_span: dummy_span,
                         name: "Object".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Items(vec![StructItem::Field(StructField {
                             version: None,
                             name_span: dummy_span,
@@ -1643,6 +2333,7 @@ mod tests {
                                 dummy_span,
                                 "Struct".to_string(),
                                 None,
+                                Vec::new(),
                                 Some(MajorVersion(1))
                             ),
                         }),]),
@@ -1651,12 +2342,14 @@ mod tests {
                         version: MajorVersion(2),
                         name_span: dummy_span,
                         name: "Object".to_string(),
+                        generics: Vec::new(),
                         body: StructBody::Tuple(Tuple(vec![TupleItem::Field(TupleField {
                             version: None,
                             field_type: FieldType::Type(
                                 dummy_span,
                                 "Enum".to_string(),
                                 None,
+                                Vec::new(),
                                 Some(MajorVersion(1))
                             ),
                         }),])),
@@ -1665,6 +2358,7 @@ mod tests {
                         version: MajorVersion(3),
                         name_span: dummy_span,
                         name: "Object".to_string(),
+                        generics: Vec::new(),
                         items: vec![UnionItem::Field(UnionField {
                             version: None,
                             name_span: dummy_span,
@@ -1679,6 +2373,7 @@ mod tests {
                                         dummy_span,
                                         "types".to_string(),
                                         Some("Object".to_string()),
+                                        Vec::new(),
                                         None
                                     ))
                                 ),
@@ -1709,6 +2404,7 @@ mod tests {
                                     dummy_span,
                                     "Struct".to_string(),
                                     None,
+                                    Vec::new(),
                                     Some(MajorVersion(1))
                                 ),
                             }),
@@ -1722,6 +2418,7 @@ mod tests {
                                         dummy_span,
                                         "Object".to_string(),
                                         None,
+                                        Vec::new(),
                                         None,
                                     ))
                                 ),
@@ -1737,6 +2434,7 @@ mod tests {
                             dummy_span,
                             "Struct".to_string(),
                             None,
+                            Vec::new(),
                             Some(MajorVersion(1))
                         )),
                     }),
@@ -1755,6 +2453,7 @@ mod tests {
                                         dummy_span,
                                         "Object".to_string(),
                                         None,
+                                        Vec::new(),
                                         None,
                                     ))
                                 )
@@ -1769,6 +2468,7 @@ mod tests {
                                         dummy_span,
                                         "types".to_string(),
                                         Some("Object".to_string()),
+                                        Vec::new(),
                                         None,
                                     ))
                                 )
@@ -1779,4 +2479,605 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn consts_and_aliases() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            const MAX_NAME: u32 = 64;
+            const MIN_SCORE: i16 = -10;
+            const RATIO: f64 = 0.5;
+            const ENABLED: bool = true;
+            const GREETING: str = "hello";
+            type UserId = u64;
+
+            @ver(1)
+            struct User {
+                id: UserId,
+                name: [u8; MAX_NAME],
+            }
+        })
+        .unwrap();
+        let dummy_span = Span::call_site();
+        assert_eq!(
+            scheme.consts,
+            vec![
+                Const {
+                    name_span: dummy_span,
+                    name: "MAX_NAME".to_string(),
+                    const_type: Primitive::UInt32,
+                    value: ConstValue::Int(64),
+                },
+                Const {
+                    name_span: dummy_span,
+                    name: "MIN_SCORE".to_string(),
+                    const_type: Primitive::Int16,
+                    value: ConstValue::Int(-10),
+                },
+                Const {
+                    name_span: dummy_span,
+                    name: "RATIO".to_string(),
+                    const_type: Primitive::Float64,
+                    value: ConstValue::Float(0.5),
+                },
+                Const {
+                    name_span: dummy_span,
+                    name: "ENABLED".to_string(),
+                    const_type: Primitive::Boolean,
+                    value: ConstValue::Bool(true),
+                },
+                Const {
+                    name_span: dummy_span,
+                    name: "GREETING".to_string(),
+                    const_type: Primitive::String,
+                    value: ConstValue::String("hello".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            scheme.aliases,
+            vec![TypeAlias {
+                name_span: dummy_span,
+                name: "UserId".to_string(),
+                field_type: FieldType::Primitive(dummy_span, Primitive::UInt64),
+            }]
+        );
+        assert_eq!(
+            scheme.types,
+            vec![Type::Struct(Struct {
+                version: MajorVersion(1),
+                name_span: dummy_span,
+                name: "User".to_string(),
+                generics: Vec::new(),
+                body: StructBody::Items(vec![
+                    StructItem::Field(StructField {
+                        version: None,
+                        name_span: dummy_span,
+                        name: "id".to_string(),
+                        field_type: FieldType::Type(
+                            dummy_span,
+                            "UserId".to_string(),
+                            None,
+                            Vec::new(),
+                            None
+                        ),
+                    }),
+                    StructItem::Field(StructField {
+                        version: None,
+                        name_span: dummy_span,
+                        name: "name".to_string(),
+                        field_type: FieldType::Array(
+                            dummy_span,
+                            Box::new(FieldType::Primitive(dummy_span, Primitive::UInt8)),
+                            ArraySize::Const(dummy_span, "MAX_NAME".to_string()),
+                        ),
+                    }),
+                ]),
+            })]
+        );
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; const A: u8 = 256; },
+                vec!["constant value is out of range for its type"],
+            ),
+            (
+                quote! { sch "a"; const A: u32 = -1; },
+                vec!["constant value is out of range for its type"],
+            ),
+            (
+                quote! { sch "a"; const A: f64 = 1e999; },
+                vec!["constant value is out of range for its type"],
+            ),
+            (
+                quote! { sch "a"; const A: f32 = -1e39; },
+                vec!["constant value is out of range for its type"],
+            ),
+            (
+                quote! { sch "a"; const A: bool = 1; },
+                vec!["expected boolean literal"],
+            ),
+            (
+                quote! { sch "a"; const A: [u8] = 1; },
+                vec!["constant type must be an integer, float, bool, char or str primitive"],
+            ),
+            (
+                quote! { sch "a"; const A: any = 1; },
+                vec!["constant type must be an integer, float, bool, char or str primitive"],
+            ),
+        ] {
+            assert_eq!(parse_errors(tokens.clone()), messages, "{}", tokens);
+        }
+    }
+
+    #[test]
+    fn generic_types() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+                next: ?str,
+            }
+
+            @ver(1)
+            union Result<T, E> {
+                Ok(T),
+                Err(E),
+            }
+
+            @ver(1)
+            struct Users {
+                page: Page<User@ver(2)>@ver(1),
+                result: Result<Page<u64>@ver(1), str>@ver(1),
+            }
+        })
+        .unwrap();
+        let dummy_span = Span::call_site();
+        let param = |name: &str| GenericParam {
+            name_span: dummy_span,
+            name: name.to_string(),
+        };
+        let type_ = |name: &str, generic_args, version| {
+            FieldType::Type(dummy_span, name.to_string(), None, generic_args, version)
+        };
+        assert_eq!(
+            scheme.types,
+            vec![
+                Type::Struct(Struct {
+                    version: MajorVersion(1),
+                    name_span: dummy_span,
+                    name: "Page".to_string(),
+                    generics: vec![param("T")],
+                    body: StructBody::Items(vec![
+                        StructItem::Field(StructField {
+                            version: None,
+                            name_span: dummy_span,
+                            name: "items".to_string(),
+                            field_type: FieldType::List(
+                                dummy_span,
+                                Box::new(type_("T", Vec::new(), None)),
+                            ),
+                        }),
+                        StructItem::Field(StructField {
+                            version: None,
+                            name_span: dummy_span,
+                            name: "next".to_string(),
+                            field_type: FieldType::Optional(
+                                dummy_span,
+                                Box::new(FieldType::Primitive(dummy_span, Primitive::String)),
+                            ),
+                        }),
+                    ]),
+                }),
+                Type::Union(Union {
+                    version: MajorVersion(1),
+                    name_span: dummy_span,
+                    name: "Result".to_string(),
+                    generics: vec![param("T"), param("E")],
+                    items: vec![
+                        UnionItem::Field(UnionField {
+                            version: None,
+                            name_span: dummy_span,
+                            name: "Ok".to_string(),
+                            body: StructBody::Tuple(Tuple(vec![TupleItem::Field(TupleField {
+                                version: None,
+                                field_type: type_("T", Vec::new(), None),
+                            })])),
+                        }),
+                        UnionItem::Field(UnionField {
+                            version: None,
+                            name_span: dummy_span,
+                            name: "Err".to_string(),
+                            body: StructBody::Tuple(Tuple(vec![TupleItem::Field(TupleField {
+                                version: None,
+                                field_type: type_("E", Vec::new(), None),
+                            })])),
+                        }),
+                    ],
+                }),
+                Type::Struct(Struct {
+                    version: MajorVersion(1),
+                    name_span: dummy_span,
+                    name: "Users".to_string(),
+                    generics: Vec::new(),
+                    body: StructBody::Items(vec![
+                        StructItem::Field(StructField {
+                            version: None,
+                            name_span: dummy_span,
+                            name: "page".to_string(),
+                            field_type: type_(
+                                "Page",
+                                vec![type_("User", Vec::new(), Some(MajorVersion(2)))],
+                                Some(MajorVersion(1)),
+                            ),
+                        }),
+                        StructItem::Field(StructField {
+                            version: None,
+                            name_span: dummy_span,
+                            name: "result".to_string(),
+                            field_type: type_(
+                                "Result",
+                                vec![
+                                    type_(
+                                        "Page",
+                                        vec![FieldType::Primitive(dummy_span, Primitive::UInt64)],
+                                        Some(MajorVersion(1)),
+                                    ),
+                                    FieldType::Primitive(dummy_span, Primitive::String),
+                                ],
+                                Some(MajorVersion(1)),
+                            ),
+                        }),
+                    ]),
+                }),
+            ]
+        );
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; @ver(1) struct S<> { a: u8, } },
+                vec!["expected at least one generic parameter"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S<T { a: T, } },
+                vec!["expected `,`"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: Page<u8@ver(1), } },
+                vec!["expected `,`"],
+            ),
+        ] {
+            assert_eq!(parse_errors(tokens.clone()), messages, "{}", tokens);
+        }
+    }
+
+    #[test]
+    fn recovers_from_errors() {
+        let err = syn::parse2::<Scheme>(quote! {
+            sch "scheme/name";
+
+            use external_crate::;
+
+            @ver(1)
+            struct Struct {
+                one: u8,
+                two: i9 i9,
+                three: [u8; 32; 1],
+                four: u8,
+                five: ?,
+            }
+
+            @ver(1)
+            fn Function (
+                one: u8,
+                two: ,
+            ) -> Struct@ver(1)
+
+            @ver(1)
+            enum Enum {
+                Zero = 1.5,
+                One,
+                @add(1.1)
+                Two,
+            }
+
+            @ver(1)
+            struct Valid {
+                one: u8,
+            }
+
+            @ver(x)
+            union Union {
+                One,
+            }
+        })
+        .unwrap_err();
+
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "expected identifier",
+                "expected `,`",
+                "expected nothing here",
+                "expected one of: identifier, `?`, `&`, square brackets, curly braces, parentheses",
+                "expected one of: identifier, `?`, `&`, square brackets, curly braces, parentheses",
+                "expected integer literal",
+                "@add directive is not allowed for enum fields",
+                "expected integer literal",
+            ]
+        );
+    }
+
+    #[test]
+    fn additional_primitives() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            const MIN: i128 = -170141183460469231731687303715884105728;
+            const MAX: u128 = 340282366920938463463374607431768211455;
+            const SMALL: u128 = 1;
+            const SEPARATOR: char = ',';
+
+            @ver(1)
+            struct Struct (
+                i128,
+                u128,
+                char,
+                timestamp,
+                duration,
+                uuid,
+                decimal,
+            )
+        })
+        .unwrap();
+        let dummy_span = Span::call_site();
+        assert_eq!(
+            scheme.consts,
+            vec![
+                Const {
+                    name_span: dummy_span,
+                    name: "MIN".to_string(),
+                    const_type: Primitive::Int128,
+                    value: ConstValue::Int(i128::MIN),
+                },
+                Const {
+                    name_span: dummy_span,
+                    name: "MAX".to_string(),
+                    const_type: Primitive::UInt128,
+                    value: ConstValue::UInt(u128::MAX),
+                },
+                Const {
+                    name_span: dummy_span,
+                    name: "SMALL".to_string(),
+                    const_type: Primitive::UInt128,
+                    value: ConstValue::Int(1),
+                },
+                Const {
+                    name_span: dummy_span,
+                    name: "SEPARATOR".to_string(),
+                    const_type: Primitive::Char,
+                    value: ConstValue::Char(','),
+                },
+            ]
+        );
+
+        let Type::Struct(Struct {
+            body: StructBody::Tuple(Tuple(items)),
+            ..
+        }) = &scheme.types[0]
+        else {
+            panic!("expected tuple struct");
+        };
+        let primitives: Vec<&FieldType> = items
+            .iter()
+            .map(|item| match item {
+                TupleItem::Field(field) => &field.field_type,
+                TupleItem::Include(_) => panic!("expected tuple field"),
+            })
+            .collect();
+        assert_eq!(
+            primitives,
+            [
+                Primitive::Int128,
+                Primitive::UInt128,
+                Primitive::Char,
+                Primitive::Timestamp,
+                Primitive::Duration,
+                Primitive::Uuid,
+                Primitive::Decimal,
+            ]
+            .map(|primitive| FieldType::Primitive(dummy_span, primitive))
+            .iter()
+            .collect::<Vec<_>>()
+        );
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; const A: char = "a"; },
+                vec!["expected character literal"],
+            ),
+            (
+                quote! { sch "a"; const A: uuid = 1; },
+                vec!["constant type must be an integer, float, bool, char or str primitive"],
+            ),
+            (
+                quote! { sch "a"; const A: timestamp = 1; },
+                vec!["constant type must be an integer, float, bool, char or str primitive"],
+            ),
+        ] {
+            assert_eq!(parse_errors(tokens.clone()), messages, "{}", tokens);
+        }
+    }
+
+    #[test]
+    fn sets_and_ordered_collections() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            type Tags = {str};
+            type OrderedTags = {str; ord};
+            type Index = [UserId: u32];
+            type OrderedIndex = [types::UserId: u32; ord];
+        })
+        .unwrap();
+        let dummy_span = Span::call_site();
+        let string = || Box::new(FieldType::Primitive(dummy_span, Primitive::String));
+        let uint32 = || Box::new(FieldType::Primitive(dummy_span, Primitive::UInt32));
+        let field_types: Vec<&FieldType> = scheme
+            .aliases
+            .iter()
+            .map(|alias| &alias.field_type)
+            .collect();
+        assert_eq!(
+            field_types,
+            vec![
+                &FieldType::Set(dummy_span, string(), Collection::Hashed),
+                &FieldType::Set(dummy_span, string(), Collection::Ordered),
+                &FieldType::Map(
+                    dummy_span,
+                    Box::new(FieldType::Type(
+                        dummy_span,
+                        "UserId".to_string(),
+                        None,
+                        Vec::new(),
+                        None,
+                    )),
+                    uint32(),
+                    Collection::Hashed,
+                ),
+                &FieldType::Map(
+                    dummy_span,
+                    Box::new(FieldType::Type(
+                        dummy_span,
+                        "types".to_string(),
+                        Some("UserId".to_string()),
+                        Vec::new(),
+                        None,
+                    )),
+                    uint32(),
+                    Collection::Ordered,
+                ),
+            ]
+        );
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; type A = {u8, u8}; },
+                vec!["expected nothing here"],
+            ),
+            (
+                quote! { sch "a"; type A = {u8; hash}; },
+                vec!["expected `ord`"],
+            ),
+            (
+                quote! { sch "a"; type A = [u8; ord; ord]; },
+                vec!["expected nothing here"],
+            ),
+            (
+                quote! { sch "a"; type A = {}; },
+                vec!["unexpected end of input, expected one of: identifier, `?`, `&`, square brackets, curly braces, parentheses"],
+            ),
+        ] {
+            assert_eq!(parse_errors(tokens.clone()), messages, "{}", tokens);
+        }
+    }
+
+    #[test]
+    fn flags() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            flags Permissions {
+                Read = 0,
+                Write = 1,
+
+                @add(1.1)
+                Execute = 2,
+
+                @add(1.1) @rem(1.2)
+                Admin = 63,
+            }
+
+            @ver(2)
+            flags Permissions {
+                @ver(1) {
+                    @rem(Admin),
+                },
+
+                @rem(2.1)
+                Delete = 3,
+            }
+        })
+        .unwrap();
+        let dummy_span = Span::call_site();
+        let field = |added, removed, name: &str, bit| {
+            FlagsItem::Field(FlagsField {
+                added,
+                removed,
+                name_span: dummy_span,
+                name: name.to_string(),
+                bit_span: dummy_span,
+                bit,
+            })
+        };
+        assert_eq!(
+            scheme.types,
+            vec![
+                Type::Flags(Flags {
+                    version: MajorVersion(1),
+                    name_span: dummy_span,
+                    name: "Permissions".to_string(),
+                    items: vec![
+                        field(None, None, "Read", 0),
+                        field(None, None, "Write", 1),
+                        field(Some(MinorVersion(1, 1)), None, "Execute", 2),
+                        field(
+                            Some(MinorVersion(1, 1)),
+                            Some(MinorVersion(1, 2)),
+                            "Admin",
+                            63
+                        ),
+                    ],
+                }),
+                Type::Flags(Flags {
+                    version: MajorVersion(2),
+                    name_span: dummy_span,
+                    name: "Permissions".to_string(),
+                    items: vec![
+                        FlagsItem::Include(Include {
+                            version: MajorVersion(1),
+                            items: vec![IncludeItem::Rem("Admin".to_string())],
+                        }),
+                        field(None, Some(MinorVersion(2, 1)), "Delete", 3),
+                    ],
+                }),
+            ]
+        );
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; @ver(1) flags F { A, } },
+                vec!["expected `=`"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) flags F { A = 64, } },
+                vec!["flag bit position must be less than 64"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) flags F { @add(1.1) @add(1.2) A = 1, } },
+                vec!["duplicate @add directive"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) flags F { @foo(1.1) A = 1, } },
+                vec!["expected `add` or `rem`"],
+            ),
+        ] {
+            assert_eq!(parse_errors(tokens.clone()), messages, "{}", tokens);
+        }
+    }
 }
@@ -0,0 +1,398 @@
+use crate::parse::{
+    EnumField, EnumItem, FlagsField, FlagsItem, Include, IncludeItem, MajorVersion, MinorVersion,
+    Object, Scheme, StructBody, StructField, StructItem, TupleField, TupleItem, Type, UnionField,
+    UnionItem,
+};
+
+/// An item list entry that is either an include block or a field.
+pub trait Item: Sized {
+    type Field: Member;
+
+    fn include(&self) -> Option<&Include>;
+    fn field(&self) -> Option<&Self::Field>;
+
+    /// Returns the items of the same kind declared by `type_def`.
+    fn items_of(type_def: &Type) -> Option<&[Self]>;
+}
+
+/// A field of a type version along with its minor version directives.
+pub trait Member {
+    fn name(&self) -> Option<&str>;
+    fn added(&self) -> Option<&MinorVersion>;
+    fn removed(&self) -> Option<&MinorVersion>;
+}
+
+/// A field of a type version after include blocks have been expanded.
+pub struct Resolved<'a, F> {
+    pub field: &'a F,
+    pub included_from: Option<&'a MajorVersion>,
+}
+
+/// Expands the include blocks in `items`, which belong to `version` of the
+/// type called `name`.
+///
+/// Included fields are dropped when the include block removes them with
+/// `@rem(name)`, when they were removed in a minor version of the included
+/// type and not added back with `@add(name)`, or when `items` declares a
+/// field of the same name.
+pub fn resolve<'a, I: Item>(
+    scheme: &'a Scheme,
+    name: &str,
+    version: &MajorVersion,
+    items: &'a [I],
+) -> Vec<Resolved<'a, I::Field>> {
+    let mut resolved = Vec::new();
+    resolve_items(scheme, name, items, &mut vec![version.0], &mut resolved);
+    resolved
+}
+
+fn resolve_items<'a, I: Item>(
+    scheme: &'a Scheme,
+    name: &str,
+    items: &'a [I],
+    visiting: &mut Vec<u16>,
+    resolved: &mut Vec<Resolved<'a, I::Field>>,
+) {
+    let own_names: Vec<&str> = items
+        .iter()
+        .filter_map(|item| item.field()?.name())
+        .collect();
+
+    for item in items {
+        if let Some(field) = item.field() {
+            resolved.push(Resolved {
+                field,
+                included_from: None,
+            });
+            continue;
+        }
+
+        let Some(include) = item.include() else {
+            continue;
+        };
+        // Cyclic includes are not expanded again.
+        if visiting.contains(&include.version.0) {
+            continue;
+        }
+        let Some(included_items) = scheme
+            .types
+            .iter()
+            .filter(|type_def| type_def.name() == name && type_def.version() == &include.version)
+            .find_map(I::items_of)
+        else {
+            continue;
+        };
+
+        let mut included = Vec::new();
+        visiting.push(include.version.0);
+        resolve_items(scheme, name, included_items, visiting, &mut included);
+        visiting.pop();
+
+        for member in included {
+            if let Some(field_name) = member.field.name() {
+                let has_directive = |directive: &IncludeItem| match directive {
+                    IncludeItem::Add(name) | IncludeItem::Rem(name) => name == field_name,
+                };
+                let removed = include
+                    .items
+                    .iter()
+                    .any(|item| matches!(item, IncludeItem::Rem(_)) && has_directive(item));
+                let added_back = include
+                    .items
+                    .iter()
+                    .any(|item| matches!(item, IncludeItem::Add(_)) && has_directive(item));
+
+                if removed
+                    || own_names.contains(&field_name)
+                    || (member.field.removed().is_some() && !added_back)
+                {
+                    continue;
+                }
+            }
+
+            resolved.push(Resolved {
+                field: member.field,
+                included_from: Some(member.included_from.unwrap_or(&include.version)),
+            });
+        }
+    }
+}
+
+impl Item for StructItem {
+    type Field = StructField;
+
+    fn include(&self) -> Option<&Include> {
+        match self {
+            Self::Include(include) => Some(include),
+            Self::Field(_) => None,
+        }
+    }
+
+    fn field(&self) -> Option<&StructField> {
+        match self {
+            Self::Include(_) => None,
+            Self::Field(field) => Some(field),
+        }
+    }
+
+    fn items_of(type_def: &Type) -> Option<&[Self]> {
+        match type_def {
+            Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => match &struct_.body {
+                StructBody::Items(items) => Some(items),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Item for TupleItem {
+    type Field = TupleField;
+
+    fn include(&self) -> Option<&Include> {
+        match self {
+            Self::Include(include) => Some(include),
+            Self::Field(_) => None,
+        }
+    }
+
+    fn field(&self) -> Option<&TupleField> {
+        match self {
+            Self::Include(_) => None,
+            Self::Field(field) => Some(field),
+        }
+    }
+
+    fn items_of(type_def: &Type) -> Option<&[Self]> {
+        match type_def {
+            Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => match &struct_.body {
+                StructBody::Tuple(tuple) => Some(&tuple.0),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Item for UnionItem {
+    type Field = UnionField;
+
+    fn include(&self) -> Option<&Include> {
+        match self {
+            Self::Include(include) => Some(include),
+            Self::Field(_) => None,
+        }
+    }
+
+    fn field(&self) -> Option<&UnionField> {
+        match self {
+            Self::Include(_) => None,
+            Self::Field(field) => Some(field),
+        }
+    }
+
+    fn items_of(type_def: &Type) -> Option<&[Self]> {
+        match type_def {
+            Type::Union(union_) | Type::Object(Object::Union(union_)) => Some(&union_.items),
+            _ => None,
+        }
+    }
+}
+
+impl Item for EnumItem {
+    type Field = EnumField;
+
+    fn include(&self) -> Option<&Include> {
+        match self {
+            Self::Include(include) => Some(include),
+            Self::Field(_) => None,
+        }
+    }
+
+    fn field(&self) -> Option<&EnumField> {
+        match self {
+            Self::Include(_) => None,
+            Self::Field(field) => Some(field),
+        }
+    }
+
+    fn items_of(type_def: &Type) -> Option<&[Self]> {
+        match type_def {
+            Type::Enum(enum_) | Type::Object(Object::Enum(enum_)) => Some(&enum_.items),
+            _ => None,
+        }
+    }
+}
+
+impl Item for FlagsItem {
+    type Field = FlagsField;
+
+    fn include(&self) -> Option<&Include> {
+        match self {
+            Self::Include(include) => Some(include),
+            Self::Field(_) => None,
+        }
+    }
+
+    fn field(&self) -> Option<&FlagsField> {
+        match self {
+            Self::Include(_) => None,
+            Self::Field(field) => Some(field),
+        }
+    }
+
+    fn items_of(type_def: &Type) -> Option<&[Self]> {
+        match type_def {
+            Type::Flags(flags) => Some(&flags.items),
+            _ => None,
+        }
+    }
+}
+
+impl Member for StructField {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn added(&self) -> Option<&MinorVersion> {
+        self.version.as_ref()
+    }
+
+    fn removed(&self) -> Option<&MinorVersion> {
+        None
+    }
+}
+
+impl Member for TupleField {
+    fn name(&self) -> Option<&str> {
+        None
+    }
+
+    fn added(&self) -> Option<&MinorVersion> {
+        self.version.as_ref()
+    }
+
+    fn removed(&self) -> Option<&MinorVersion> {
+        None
+    }
+}
+
+impl Member for UnionField {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn added(&self) -> Option<&MinorVersion> {
+        None
+    }
+
+    fn removed(&self) -> Option<&MinorVersion> {
+        self.version.as_ref()
+    }
+}
+
+impl Member for EnumField {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn added(&self) -> Option<&MinorVersion> {
+        None
+    }
+
+    fn removed(&self) -> Option<&MinorVersion> {
+        self.version.as_ref()
+    }
+}
+
+impl Member for FlagsField {
+    fn name(&self) -> Option<&str> {
+        Some(&self.name)
+    }
+
+    fn added(&self) -> Option<&MinorVersion> {
+        self.added.as_ref()
+    }
+
+    fn removed(&self) -> Option<&MinorVersion> {
+        self.removed.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn expands_includes() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            struct Struct {
+                one: u8,
+                two: u8,
+                @add(1.1)
+                three: u8,
+            }
+
+            @ver(2)
+            struct Struct {
+                @ver(1) {
+                    @rem(two),
+                },
+                three: u16,
+                four: u8,
+            }
+
+            @ver(1)
+            enum Enum {
+                @rem(1.1)
+                Zero,
+                One,
+                @rem(1.2)
+                Two,
+            }
+
+            @ver(2)
+            enum Enum {
+                @ver(1) {
+                    @add(Two),
+                },
+                Three,
+            }
+        })
+        .unwrap();
+
+        let Type::Struct(struct_) = &scheme.types[1] else {
+            panic!("expected struct");
+        };
+        let StructBody::Items(items) = &struct_.body else {
+            panic!("expected struct fields");
+        };
+        let fields: Vec<(&str, Option<u16>)> =
+            resolve(&scheme, &struct_.name, &struct_.version, items)
+                .iter()
+                .map(|member| {
+                    (
+                        member.field.name.as_str(),
+                        member.included_from.map(|version| version.0),
+                    )
+                })
+                .collect();
+        assert_eq!(
+            fields,
+            vec![("one", Some(1)), ("three", None), ("four", None)]
+        );
+
+        let Type::Enum(enum_) = &scheme.types[3] else {
+            panic!("expected enum");
+        };
+        let fields: Vec<&str> = resolve(&scheme, &enum_.name, &enum_.version, &enum_.items)
+            .iter()
+            .map(|member| member.field.name.as_str())
+            .collect();
+        assert_eq!(fields, vec!["One", "Two", "Three"]);
+    }
+}
@@ -0,0 +1,1431 @@
+use std::collections::HashMap;
+
+use proc_macro2::Span;
+use syn::{Error, Result};
+
+use crate::{
+    parse::{
+        ArraySize, Collection, Const, ConstValue, EnumItem, Errors, FieldType, Include,
+        IncludeItem, MajorVersion, Object, Primitive, Scheme, StructBody, StructItem, Tuple,
+        TupleItem, Type, TypeAlias, UnionItem, Use,
+    },
+    resolve::{resolve, Resolved},
+};
+
+pub fn validate(scheme: &Scheme) -> Result<()> {
+    let mut errors = Errors::default();
+
+    let mut names = HashMap::new();
+    for const_ in &scheme.consts {
+        errors.check(check_unique(&mut names, &const_.name, const_.name_span));
+    }
+    for alias in &scheme.aliases {
+        errors.check(check_unique(&mut names, &alias.name, alias.name_span));
+    }
+    for type_def in &scheme.types {
+        if let Some(&span) = names.get(type_def.name()) {
+            let mut err = Error::new(
+                span,
+                format!(
+                    "`{}` conflicts with a type of the same name",
+                    type_def.name()
+                ),
+            );
+            err.combine(Error::new(
+                type_def.name_span(),
+                format!("`{}` defined here", type_def.name()),
+            ));
+            errors.push(err);
+        }
+    }
+
+    let mut versions = HashMap::new();
+    for type_def in &scheme.types {
+        let key = (type_def.name(), type_def.version().0);
+        match versions.get(&key) {
+            Some(&previous_span) => errors.push(duplicate_error(
+                &format!("{}@ver({})", key.0, key.1),
+                type_def.name_span(),
+                previous_span,
+            )),
+            None => {
+                versions.insert(key, type_def.name_span());
+            }
+        }
+
+        check_unique_fields(scheme, type_def, &mut errors);
+        check_generic_params(type_def, &mut errors);
+    }
+
+    let consts: HashMap<&str, &Const> = scheme
+        .consts
+        .iter()
+        .map(|const_| (const_.name.as_str(), const_))
+        .collect();
+    let aliases: HashMap<&str, &TypeAlias> = scheme
+        .aliases
+        .iter()
+        .map(|alias| (alias.name.as_str(), alias))
+        .collect();
+
+    for alias in &scheme.aliases {
+        errors.check(check_alias_cycle(&aliases, alias, &mut Vec::new()));
+    }
+
+    let graph = ValueGraph {
+        scheme,
+        aliases: &aliases,
+    };
+    graph.check_recursion(&mut errors);
+
+    visit_scheme(scheme, &mut |field_type| match field_type {
+        FieldType::Array(_, _, ArraySize::Const(span, name)) => {
+            errors.check(check_array_size(&consts, *span, name));
+        }
+        FieldType::Type(span, name, None, generic_args, version)
+            if aliases.contains_key(name.as_str()) =>
+        {
+            if version.is_some() {
+                errors.push(Error::new(
+                    *span,
+                    format!("type alias `{}` cannot be versioned", name),
+                ));
+            } else if !generic_args.is_empty() {
+                errors.push(Error::new(
+                    *span,
+                    format!("type alias `{}` does not take generic arguments", name),
+                ));
+            }
+        }
+        FieldType::Map(_, key_type, ..) | FieldType::Set(_, key_type, _) => {
+            check_key_type(
+                &graph,
+                key_type,
+                &mut Vec::new(),
+                &mut Vec::new(),
+                &mut errors,
+            );
+        }
+        FieldType::Type(span, name, None, generic_args, Some(version)) => {
+            errors.check(check_generic_args(
+                scheme,
+                *span,
+                name,
+                generic_args.len(),
+                version,
+            ));
+        }
+        _ => {}
+    });
+
+    errors.finish()
+}
+
+/// Validates the references in `scheme` to types of `use`d schemes, using
+/// `find_scheme` to look up the scheme a `use` item brings into scope.
+/// References into schemes `find_scheme` does not find are not checked,
+/// but naming a scheme no `use` item brings into scope is an error.
+///
+/// `define!` looks schemes up through the hidden macro each scheme exports,
+/// and the `cycle` tool among the files it is given.
+pub fn validate_externs<'a>(
+    scheme: &Scheme,
+    find_scheme: impl Fn(&Use) -> Option<&'a Scheme>,
+) -> Result<()> {
+    let mut errors = Errors::default();
+    visit_scheme(scheme, &mut |field_type| match field_type {
+        FieldType::Type(span, alias, Some(name), generic_args, version) => {
+            errors.check(check_extern_type(
+                scheme,
+                &find_scheme,
+                *span,
+                alias,
+                name,
+                generic_args.len(),
+                version.as_ref(),
+            ));
+        }
+        FieldType::Reference(span, inner) => {
+            let FieldType::Type(_, alias, Some(name), ..) = &**inner else {
+                return;
+            };
+            let Some(extern_scheme) = find_extern_scheme(scheme, &find_scheme, alias) else {
+                return;
+            };
+
+            let mut versions = extern_scheme
+                .types
+                .iter()
+                .filter(|type_def| type_def.name() == name)
+                .peekable();
+            if versions.peek().is_some()
+                && !versions.all(|type_def| matches!(type_def, Type::Object(_)))
+            {
+                errors.push(Error::new(
+                    *span,
+                    format!("`{}::{}` is not an object type", alias, name),
+                ));
+            }
+        }
+        _ => {}
+    });
+    errors.finish()
+}
+
+fn find_extern_scheme<'a>(
+    scheme: &Scheme,
+    find_scheme: &impl Fn(&Use) -> Option<&'a Scheme>,
+    alias: &str,
+) -> Option<&'a Scheme> {
+    let use_ = scheme.uses.iter().find(|use_| use_.name() == alias)?;
+    find_scheme(use_)
+}
+
+fn check_extern_type<'a>(
+    scheme: &Scheme,
+    find_scheme: &impl Fn(&Use) -> Option<&'a Scheme>,
+    span: Span,
+    alias: &str,
+    name: &str,
+    arg_count: usize,
+    version: Option<&MajorVersion>,
+) -> Result<()> {
+    let Some(use_) = scheme.uses.iter().find(|use_| use_.name() == alias) else {
+        return Err(Error::new(
+            span,
+            format!("`{}` is not a used scheme", alias),
+        ));
+    };
+    let Some(extern_scheme) = find_scheme(use_) else {
+        return Ok(());
+    };
+
+    let versions: Vec<&Type> = extern_scheme
+        .types
+        .iter()
+        .filter(|type_def| type_def.name() == name)
+        .collect();
+    if versions.is_empty() {
+        return Err(Error::new(
+            span,
+            format!(
+                "`{}::{}` is not defined in scheme {:?}",
+                alias, name, extern_scheme.name
+            ),
+        ));
+    }
+
+    let Some(version) = version else {
+        return Ok(());
+    };
+    let Some(type_def) = versions
+        .iter()
+        .find(|type_def| type_def.version() == version)
+    else {
+        let available: Vec<String> = versions
+            .iter()
+            .map(|type_def| type_def.version().0.to_string())
+            .collect();
+        return Err(Error::new(
+            span,
+            format!(
+                "`{}::{}@ver({})` is not defined, available versions: {}",
+                alias,
+                name,
+                version.0,
+                available.join(", ")
+            ),
+        ));
+    };
+
+    let kind = match type_def {
+        Type::Function(_) => "function",
+        Type::Command(_) => "command",
+        _ => return check_generic_args(extern_scheme, span, name, arg_count, version),
+    };
+    Err(Error::new(
+        span,
+        format!(
+            "`{}::{}@ver({})` is a {} and cannot be used as a field type",
+            alias, name, version.0, kind
+        ),
+    ))
+}
+
+fn duplicate_error(name: &str, span: Span, previous_span: Span) -> Error {
+    let mut err = Error::new(span, format!("`{}` is defined more than once", name));
+    err.combine(Error::new(
+        previous_span,
+        format!("`{}` previously defined here", name),
+    ));
+    err
+}
+
+fn check_unique<'a>(names: &mut HashMap<&'a str, Span>, name: &'a str, span: Span) -> Result<()> {
+    match names.get(name) {
+        Some(&previous_span) => Err(duplicate_error(name, span, previous_span)),
+        None => {
+            names.insert(name, span);
+            Ok(())
+        }
+    }
+}
+
+fn check_unique_fields(scheme: &Scheme, type_def: &Type, errors: &mut Errors) {
+    match type_def {
+        Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => {
+            check_unique_struct_fields(&struct_.body, errors);
+        }
+        Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+            let mut names = HashMap::new();
+            for item in &union_.items {
+                if let UnionItem::Field(field) = item {
+                    errors.check(check_unique(&mut names, &field.name, field.name_span));
+                    check_unique_struct_fields(&field.body, errors);
+                }
+            }
+        }
+        Type::Enum(enum_) | Type::Object(Object::Enum(enum_)) => {
+            let mut names = HashMap::new();
+            for item in &enum_.items {
+                if let EnumItem::Field(field) = item {
+                    errors.check(check_unique(&mut names, &field.name, field.name_span));
+                }
+            }
+        }
+        // Included flags keep their bits, so they are checked along with the
+        // flags declared in this version.
+        Type::Flags(flags) => {
+            let mut names = HashMap::new();
+            let mut bits = HashMap::new();
+            for Resolved { field, .. } in resolve(scheme, &flags.name, &flags.version, &flags.items)
+            {
+                errors.check(check_unique(&mut names, &field.name, field.name_span));
+                match bits.get(&field.bit) {
+                    Some(&previous_span) => {
+                        let mut err = Error::new(
+                            field.bit_span,
+                            format!("bit {} is used by more than one flag", field.bit),
+                        );
+                        err.combine(Error::new(
+                            previous_span,
+                            format!("bit {} previously used here", field.bit),
+                        ));
+                        errors.push(err);
+                    }
+                    None => {
+                        bits.insert(field.bit, field.bit_span);
+                    }
+                }
+            }
+        }
+        Type::Function(function) => check_unique_struct_items(&function.items, errors),
+        Type::Command(command) => check_unique_struct_items(&command.items, errors),
+    }
+}
+
+fn check_unique_struct_fields(body: &StructBody, errors: &mut Errors) {
+    if let StructBody::Items(items) = body {
+        check_unique_struct_items(items, errors);
+    }
+}
+
+fn check_unique_struct_items(items: &[StructItem], errors: &mut Errors) {
+    let mut names = HashMap::new();
+    for item in items {
+        if let StructItem::Field(field) = item {
+            errors.check(check_unique(&mut names, &field.name, field.name_span));
+        }
+    }
+}
+
+fn check_array_size(consts: &HashMap<&str, &Const>, span: Span, name: &str) -> Result<()> {
+    let Some(const_) = consts.get(name) else {
+        return Err(Error::new(span, format!("cannot find constant `{}`", name)));
+    };
+
+    let is_unsigned = matches!(const_.const_type.int_range(), Some((0, _)));
+    if !is_unsigned {
+        return Err(Error::new(
+            span,
+            format!("array size constant `{}` must be an unsigned integer", name),
+        ));
+    }
+
+    match const_.value {
+        ConstValue::Int(value) if u32::try_from(value).is_ok() => Ok(()),
+        _ => Err(Error::new(
+            span,
+            format!("array size constant `{}` does not fit in u32", name),
+        )),
+    }
+}
+
+fn check_generic_params(type_def: &Type, errors: &mut Errors) {
+    let mut names = HashMap::new();
+    for param in type_def.generics() {
+        errors.check(check_unique(&mut names, &param.name, param.name_span));
+    }
+
+    visit_type(type_def, &mut |field_type| match field_type {
+        FieldType::Type(span, name, None, generic_args, version)
+            if names.contains_key(name.as_str())
+                && (!generic_args.is_empty() || version.is_some()) =>
+        {
+            errors.push(Error::new(
+                *span,
+                format!(
+                    "generic parameter `{}` cannot be versioned or take generic arguments",
+                    name
+                ),
+            ));
+        }
+        _ => {}
+    });
+}
+
+fn check_generic_args(
+    scheme: &Scheme,
+    span: Span,
+    name: &str,
+    arg_count: usize,
+    version: &MajorVersion,
+) -> Result<()> {
+    let Some(type_def) = scheme
+        .types
+        .iter()
+        .find(|type_def| type_def.name() == name && type_def.version() == version)
+    else {
+        return Ok(());
+    };
+
+    let param_count = type_def.generics().len();
+    if param_count != arg_count {
+        return Err(Error::new(
+            span,
+            format!(
+                "`{}@ver({})` takes {} generic argument(s) but {} were given",
+                name, version.0, param_count, arg_count
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that `key_type` can be hashed and ordered, looking through aliases
+/// and into the fields of local types. `seen_aliases` and `seen_types` hold
+/// the aliases and types being checked, which are not checked again.
+fn check_key_type<'a>(
+    graph: &ValueGraph<'a>,
+    key_type: &'a FieldType,
+    seen_aliases: &mut Vec<&'a str>,
+    seen_types: &mut Vec<&'a Type>,
+    errors: &mut Errors,
+) {
+    let message = match key_type {
+        FieldType::Primitive(_, Primitive::Float32 | Primitive::Float64) => {
+            "float types cannot be used in map keys or set elements"
+        }
+        FieldType::Primitive(_, Primitive::Any) => {
+            "`any` cannot be used in map keys or set elements"
+        }
+        FieldType::Primitive(..) => return,
+        FieldType::Reference(..) => "object references cannot be used in map keys or set elements",
+        FieldType::Map(_, _, _, Collection::Hashed) | FieldType::Set(_, _, Collection::Hashed) => {
+            "hashed maps and sets cannot be used in map keys or set elements, use `; ord`"
+        }
+        // The keys and elements of every map and set are checked on their
+        // own, so only the values of nested maps are left to check.
+        FieldType::Map(_, _, value_type, Collection::Ordered) => {
+            check_key_type(graph, value_type, seen_aliases, seen_types, errors);
+            return;
+        }
+        FieldType::Set(_, _, Collection::Ordered) => return,
+        FieldType::Optional(_, inner)
+        | FieldType::Array(_, inner, _)
+        | FieldType::List(_, inner) => {
+            check_key_type(graph, inner, seen_aliases, seen_types, errors);
+            return;
+        }
+        FieldType::Tuple(_, tuple) => {
+            for item in &tuple.0 {
+                if let TupleItem::Field(field) = item {
+                    check_key_type(graph, &field.field_type, seen_aliases, seen_types, errors);
+                }
+            }
+            return;
+        }
+        FieldType::Type(_, name, None, _, None) => {
+            // Cyclic aliases are reported separately.
+            if let Some(alias) = graph.aliases.get(name.as_str()) {
+                if !seen_aliases.contains(&name.as_str()) {
+                    seen_aliases.push(&alias.name);
+                    check_key_type(graph, &alias.field_type, seen_aliases, seen_types, errors);
+                    seen_aliases.pop();
+                }
+            }
+            return;
+        }
+        FieldType::Type(span, name, None, generic_args, Some(version)) => {
+            for arg in generic_args {
+                check_key_type(graph, arg, seen_aliases, seen_types, errors);
+            }
+            let Some(type_def) = graph.find_type(name, version) else {
+                return;
+            };
+            if seen_types.iter().any(|seen| std::ptr::eq(*seen, type_def)) {
+                return;
+            }
+
+            let mut fields = Vec::new();
+            graph.collect_fields(type_def, &[], &mut fields, &mut Vec::new());
+            let mut field_errors = Errors::default();
+            seen_types.push(type_def);
+            for field_type in fields {
+                check_key_type(
+                    graph,
+                    field_type,
+                    seen_aliases,
+                    seen_types,
+                    &mut field_errors,
+                );
+            }
+            seen_types.pop();
+
+            if let Err(err) = field_errors.finish() {
+                errors.push(Error::new(
+                    *span,
+                    format!(
+                        "`{}@ver({})` cannot be used in map keys or set elements, as some of its fields cannot",
+                        name, version.0
+                    ),
+                ));
+                errors.push(err);
+            }
+            return;
+        }
+        // Types of other schemes cannot be checked here.
+        FieldType::Type(..) => return,
+    };
+
+    errors.push(Error::new(key_type.span(), message));
+}
+
+fn check_alias_cycle<'a>(
+    aliases: &HashMap<&str, &'a TypeAlias>,
+    alias: &'a TypeAlias,
+    path: &mut Vec<&'a str>,
+) -> Result<()> {
+    if path.contains(&alias.name.as_str()) {
+        // Cycles are reported once from each alias that is part of them.
+        if path[0] != alias.name {
+            return Ok(());
+        }
+
+        path.push(&alias.name);
+        return Err(Error::new(
+            alias.name_span,
+            format!("type alias `{}` is cyclic: {}", path[0], path.join(" -> ")),
+        ));
+    }
+
+    path.push(&alias.name);
+    let mut result = Ok(());
+    visit_field_type(&alias.field_type, &mut |field_type| {
+        if let FieldType::Type(_, name, None, _, None) = field_type {
+            if let Some(next) = aliases.get(name.as_str()) {
+                if result.is_ok() {
+                    result = check_alias_cycle(aliases, next, path);
+                }
+            }
+        }
+    });
+    path.pop();
+    result
+}
+
+/// Dependencies between local types that are stored by value, i.e. not behind
+/// `?T`, a list, a map, a set or an object reference.
+struct ValueGraph<'a> {
+    scheme: &'a Scheme,
+    aliases: &'a HashMap<&'a str, &'a TypeAlias>,
+}
+
+impl<'a> ValueGraph<'a> {
+    fn check_recursion(&self, errors: &mut Errors) {
+        let mut done: Vec<&Type> = Vec::new();
+        for type_def in &self.scheme.types {
+            if !done.iter().any(|done| std::ptr::eq(*done, type_def)) {
+                self.find_cycles(type_def, &mut Vec::new(), &mut done, errors);
+            }
+        }
+    }
+
+    fn find_cycles(
+        &self,
+        type_def: &'a Type,
+        stack: &mut Vec<&'a Type>,
+        done: &mut Vec<&'a Type>,
+        errors: &mut Errors,
+    ) {
+        stack.push(type_def);
+        for target in self.dependencies(type_def) {
+            if let Some(start) = stack
+                .iter()
+                .position(|type_def| std::ptr::eq(*type_def, target))
+            {
+                let path: Vec<String> = stack[start..]
+                    .iter()
+                    .chain([&target])
+                    .map(|type_def| format!("{}@ver({})", type_def.name(), type_def.version().0))
+                    .collect();
+                errors.push(Error::new(
+                    target.name_span(),
+                    format!(
+                        "recursive type `{}` has infinite size: {}; store it behind `?T`, a list, a map or an object reference",
+                        path[0],
+                        path.join(" -> ")
+                    ),
+                ));
+            } else if !done.iter().any(|done| std::ptr::eq(*done, target)) {
+                self.find_cycles(target, stack, done, errors);
+            }
+        }
+        stack.pop();
+        done.push(type_def);
+    }
+
+    fn dependencies(&self, type_def: &'a Type) -> Vec<&'a Type> {
+        let mut fields = Vec::new();
+        self.collect_fields(type_def, &[], &mut fields, &mut Vec::new());
+
+        let mut dependencies: Vec<&Type> = Vec::new();
+        for field_type in fields {
+            self.visit_by_value(field_type, &mut Vec::new(), &mut |field_type| {
+                if let Some(target) = self.resolve(field_type) {
+                    if !dependencies.iter().any(|dep| std::ptr::eq(*dep, target)) {
+                        dependencies.push(target);
+                    }
+                }
+            });
+        }
+        dependencies
+    }
+
+    fn resolve(&self, field_type: &FieldType) -> Option<&'a Type> {
+        let FieldType::Type(_, name, None, _, Some(version)) = field_type else {
+            return None;
+        };
+        self.find_type(name, version)
+    }
+
+    fn find_type(&self, name: &str, version: &MajorVersion) -> Option<&'a Type> {
+        self.scheme
+            .types
+            .iter()
+            .find(|type_def| type_def.name() == name && type_def.version() == version)
+    }
+
+    /// Collects the field types of `type_def`, including the ones inherited
+    /// through include blocks, skipping fields named in `removed`.
+    fn collect_fields(
+        &self,
+        type_def: &'a Type,
+        removed: &[&str],
+        fields: &mut Vec<&'a FieldType>,
+        visiting: &mut Vec<&'a Type>,
+    ) {
+        if visiting
+            .iter()
+            .any(|visiting| std::ptr::eq(*visiting, type_def))
+        {
+            return;
+        }
+        visiting.push(type_def);
+
+        let mut include = |include: &'a Include, fields: &mut Vec<&'a FieldType>| {
+            let removed: Vec<&str> = include
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    IncludeItem::Rem(name) => Some(name.as_str()),
+                    IncludeItem::Add(_) => None,
+                })
+                .collect();
+            if let Some(included) = self.find_type(type_def.name(), &include.version) {
+                self.collect_fields(included, &removed, fields, visiting);
+            }
+        };
+
+        match type_def {
+            Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => match &struct_.body {
+                StructBody::Items(items) => {
+                    for item in items {
+                        match item {
+                            StructItem::Include(item) => include(item, fields),
+                            StructItem::Field(field) => {
+                                if !removed.contains(&field.name.as_str()) {
+                                    fields.push(&field.field_type);
+                                }
+                            }
+                        }
+                    }
+                }
+                StructBody::Tuple(tuple) => {
+                    for item in &tuple.0 {
+                        match item {
+                            TupleItem::Include(item) => include(item, fields),
+                            TupleItem::Field(field) => fields.push(&field.field_type),
+                        }
+                    }
+                }
+                StructBody::Unit => {}
+            },
+            Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+                for item in &union_.items {
+                    match item {
+                        UnionItem::Include(item) => include(item, fields),
+                        UnionItem::Field(field) => {
+                            if !removed.contains(&field.name.as_str()) {
+                                visit_struct_body(&field.body, &mut |field_type| {
+                                    fields.push(field_type)
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            Type::Enum(_)
+            | Type::Object(Object::Enum(_))
+            | Type::Flags(_)
+            | Type::Function(_)
+            | Type::Command(_) => {}
+        }
+
+        visiting.pop();
+    }
+
+    /// Calls `visit` for every type reference that `field_type` stores by
+    /// value, looking through type aliases and into the generic arguments
+    /// that the referenced type stores by value.
+    fn visit_by_value(
+        &self,
+        field_type: &'a FieldType,
+        visiting: &mut Vec<&'a str>,
+        visit: &mut dyn FnMut(&'a FieldType),
+    ) {
+        match field_type {
+            FieldType::Primitive(..)
+            | FieldType::Optional(..)
+            | FieldType::Reference(..)
+            | FieldType::List(..)
+            | FieldType::Map(..)
+            | FieldType::Set(..) => {}
+            FieldType::Array(_, inner, _) => self.visit_by_value(inner, visiting, visit),
+            FieldType::Tuple(_, tuple) => {
+                for item in &tuple.0 {
+                    if let TupleItem::Field(field) = item {
+                        self.visit_by_value(&field.field_type, visiting, visit);
+                    }
+                }
+            }
+            FieldType::Type(_, name, None, _, None) if self.aliases.contains_key(name.as_str()) => {
+                // Cyclic aliases are reported separately.
+                if !visiting.contains(&name.as_str()) {
+                    visiting.push(name);
+                    self.visit_by_value(&self.aliases[name.as_str()].field_type, visiting, visit);
+                    visiting.pop();
+                }
+            }
+            FieldType::Type(_, _, _, generic_args, _) => {
+                visit(field_type);
+                if let Some(target) = self.resolve(field_type) {
+                    for (param, arg) in target.generics().iter().zip(generic_args) {
+                        if self.stores_param_by_value(target, &param.name, &mut Vec::new()) {
+                            self.visit_by_value(arg, visiting, visit);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn stores_param_by_value(
+        &self,
+        type_def: &'a Type,
+        param: &str,
+        visiting: &mut Vec<&'a Type>,
+    ) -> bool {
+        if visiting
+            .iter()
+            .any(|visiting| std::ptr::eq(*visiting, type_def))
+        {
+            return false;
+        }
+        visiting.push(type_def);
+
+        let mut fields = Vec::new();
+        self.collect_fields(type_def, &[], &mut fields, &mut Vec::new());
+
+        let mut found = false;
+        for field_type in fields {
+            self.visit_by_value(field_type, &mut Vec::new(), &mut |field_type| {
+                if let FieldType::Type(_, name, None, generic_args, None) = field_type {
+                    found |= name == param && generic_args.is_empty();
+                }
+            });
+        }
+
+        visiting.pop();
+        found
+    }
+}
+
+pub fn visit_scheme<'a>(scheme: &'a Scheme, visit: &mut impl FnMut(&'a FieldType)) {
+    for alias in &scheme.aliases {
+        visit_field_type(&alias.field_type, visit);
+    }
+
+    for type_def in &scheme.types {
+        visit_type(type_def, visit);
+    }
+}
+
+pub fn visit_type<'a>(type_def: &'a Type, visit: &mut impl FnMut(&'a FieldType)) {
+    match type_def {
+        Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => {
+            visit_struct_body(&struct_.body, visit);
+        }
+        Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+            for item in &union_.items {
+                if let UnionItem::Field(field) = item {
+                    visit_struct_body(&field.body, visit);
+                }
+            }
+        }
+        Type::Enum(_) | Type::Object(Object::Enum(_)) | Type::Flags(_) => {}
+        Type::Function(function) => {
+            visit_struct_items(&function.items, visit);
+            if let Some(return_type) = &function.return_type {
+                visit_field_type(return_type, visit);
+            }
+        }
+        Type::Command(command) => visit_struct_items(&command.items, visit),
+    }
+}
+
+fn visit_struct_body<'a>(body: &'a StructBody, visit: &mut impl FnMut(&'a FieldType)) {
+    match body {
+        StructBody::Items(items) => visit_struct_items(items, visit),
+        StructBody::Tuple(tuple) => visit_tuple(tuple, visit),
+        StructBody::Unit => {}
+    }
+}
+
+fn visit_struct_items<'a>(items: &'a [StructItem], visit: &mut impl FnMut(&'a FieldType)) {
+    for item in items {
+        if let StructItem::Field(field) = item {
+            visit_field_type(&field.field_type, visit);
+        }
+    }
+}
+
+fn visit_tuple<'a>(tuple: &'a Tuple, visit: &mut impl FnMut(&'a FieldType)) {
+    for item in &tuple.0 {
+        if let TupleItem::Field(field) = item {
+            visit_field_type(&field.field_type, visit);
+        }
+    }
+}
+
+pub fn visit_field_type<'a>(field_type: &'a FieldType, visit: &mut impl FnMut(&'a FieldType)) {
+    visit(field_type);
+    match field_type {
+        FieldType::Primitive(..) | FieldType::Type(..) => {}
+        FieldType::Optional(_, inner)
+        | FieldType::Reference(_, inner)
+        | FieldType::Array(_, inner, _)
+        | FieldType::List(_, inner)
+        | FieldType::Set(_, inner, _) => visit_field_type(inner, visit),
+        FieldType::Map(_, key_type, value_type, _) => {
+            visit_field_type(key_type, visit);
+            visit_field_type(value_type, visit);
+        }
+        FieldType::Tuple(_, tuple) => visit_tuple(tuple, visit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    fn validate_tokens(tokens: proc_macro2::TokenStream) -> Result<()> {
+        validate(&syn::parse2(tokens)?)
+    }
+
+    /// Returns the messages of the errors validating `tokens` reports. The
+    /// tokens must parse.
+    fn error_messages(tokens: proc_macro2::TokenStream) -> Vec<String> {
+        let scheme = syn::parse2(tokens).unwrap();
+        match validate(&scheme) {
+            Ok(()) => Vec::new(),
+            Err(err) => err.into_iter().map(|err| err.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn consts_and_aliases() {
+        validate_tokens(quote! {
+            sch "scheme/name";
+
+            const MAX_NAME: u32 = 64;
+            type UserId = u64;
+            type Names = [Name];
+            type Name = [u8; MAX_NAME];
+
+            @ver(1)
+            struct User {
+                id: UserId,
+                name: [u8; MAX_NAME],
+                names: Names,
+            }
+        })
+        .unwrap();
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; const A: u8 = 1; type A = u8; },
+                vec![
+                    "`A` is defined more than once",
+                    "`A` previously defined here",
+                ],
+            ),
+            (
+                quote! { sch "a"; type A = u8; @ver(1) struct A; },
+                vec![
+                    "`A` conflicts with a type of the same name",
+                    "`A` defined here",
+                ],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: [u8; MISSING], } },
+                vec!["cannot find constant `MISSING`"],
+            ),
+            (
+                quote! { sch "a"; const N: i32 = 1; @ver(1) struct S { a: [u8; N], } },
+                vec!["array size constant `N` must be an unsigned integer"],
+            ),
+            (
+                quote! { sch "a"; const N: u64 = 4294967296; @ver(1) struct S { a: [u8; N], } },
+                vec!["array size constant `N` does not fit in u32"],
+            ),
+            (
+                quote! { sch "a"; type A = u8; @ver(1) struct S { a: A@ver(1), } },
+                vec!["type alias `A` cannot be versioned"],
+            ),
+            (
+                quote! { sch "a"; type A = [B]; type B = ?A; },
+                vec![
+                    "type alias `A` is cyclic: A -> B -> A",
+                    "type alias `B` is cyclic: B -> A -> B",
+                ],
+            ),
+        ] {
+            assert_eq!(error_messages(tokens.clone()), messages, "{}", tokens);
+        }
+    }
+
+    #[test]
+    fn generic_types() {
+        validate_tokens(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+                next: ?str,
+            }
+
+            @ver(1)
+            struct Users {
+                page: Page<User@ver(2)>@ver(1),
+                nested: Page<Page<u8>@ver(1)>@ver(1),
+            }
+        })
+        .unwrap();
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; @ver(1) struct S<T, T> { a: T, } },
+                vec![
+                    "`T` is defined more than once",
+                    "`T` previously defined here",
+                ],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S<T> { a: T@ver(1), } },
+                vec!["generic parameter `T` cannot be versioned or take generic arguments"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S<T> { a: T<u8>, } },
+                vec!["generic parameter `T` cannot be versioned or take generic arguments"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S<T> { a: T, } @ver(1) struct U { s: S@ver(1), } },
+                vec!["`S@ver(1)` takes 1 generic argument(s) but 0 were given"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: u8, } @ver(1) struct U { s: S<u8>@ver(1), } },
+                vec!["`S@ver(1)` takes 0 generic argument(s) but 1 were given"],
+            ),
+            (
+                quote! { sch "a"; type A = u8; @ver(1) struct S { a: A<u8>, } },
+                vec!["type alias `A` does not take generic arguments"],
+            ),
+        ] {
+            assert_eq!(error_messages(tokens.clone()), messages, "{}", tokens);
+        }
+    }
+
+    #[test]
+    fn reports_all_errors() {
+        let err = validate_tokens(quote! {
+            sch "scheme/name";
+
+            const A: u8 = 1;
+            const A: u8 = 2;
+
+            @ver(1)
+            struct Struct {
+                one: u8,
+                one: u16,
+                array: [u8; MISSING],
+            }
+
+            @ver(1)
+            struct Struct;
+
+            @ver(1)
+            union Union {
+                One,
+                One(u8),
+            }
+
+            @ver(1)
+            enum Enum {
+                One,
+                One,
+            }
+        })
+        .unwrap_err();
+
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "`A` is defined more than once",
+                "`A` previously defined here",
+                "`one` is defined more than once",
+                "`one` previously defined here",
+                "`Struct@ver(1)` is defined more than once",
+                "`Struct@ver(1)` previously defined here",
+                "`One` is defined more than once",
+                "`One` previously defined here",
+                "`One` is defined more than once",
+                "`One` previously defined here",
+                "cannot find constant `MISSING`",
+            ]
+        );
+    }
+
+    #[test]
+    fn map_keys_and_set_elements() {
+        validate_tokens(quote! {
+            sch "scheme/name";
+
+            type UserId = u64;
+
+            @ver(1)
+            struct Struct {
+                map: [UserId: f64],
+                ordered_map: [(str, i32): [f32]; ord],
+                set: {uuid},
+                ordered_set: {{[u8: u8; ord]; ord}; ord},
+                keyed_by_type: [User@ver(1): str],
+                keyed_by_tree: {Node@ver(1); ord},
+            }
+
+            @ver(1)
+            struct User {
+                id: UserId,
+                name: str,
+            }
+
+            @ver(1)
+            struct Node {
+                children: [Node@ver(1)],
+            }
+        })
+        .unwrap();
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; @ver(1) struct S { a: [f32: u8], } },
+                vec!["float types cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: {(u8, ?f64)}, } },
+                vec!["float types cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: {any; ord}, } },
+                vec!["`any` cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: [&Object: u8], } },
+                vec!["object references cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: {{u8}; ord}, } },
+                vec!["hashed maps and sets cannot be used in map keys or set elements, use `; ord`"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct S { a: {[u8: u8]}, } },
+                vec!["hashed maps and sets cannot be used in map keys or set elements, use `; ord`"],
+            ),
+            (
+                quote! { sch "a"; type F = [f32]; @ver(1) struct S { a: {F}, } },
+                vec!["float types cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct K { a: f32, } @ver(1) struct S { a: {K@ver(1)}, } },
+                vec!["`K@ver(1)` cannot be used in map keys or set elements, as some of its fields cannot", "float types cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct K (any) @ver(1) struct S { a: [K@ver(1): u8], } },
+                vec!["`K@ver(1)` cannot be used in map keys or set elements, as some of its fields cannot", "`any` cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) union K { A(&O), } @ver(1) struct S { a: {K@ver(1)}, } },
+                vec!["`K@ver(1)` cannot be used in map keys or set elements, as some of its fields cannot", "object references cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! { sch "a"; @ver(1) struct K { a: [u8: u8], } @ver(1) struct S { a: {K@ver(1); ord}, } },
+                vec!["`K@ver(1)` cannot be used in map keys or set elements, as some of its fields cannot", "hashed maps and sets cannot be used in map keys or set elements, use `; ord`"],
+            ),
+            (
+                quote! {
+                    sch "a";
+                    @ver(1) struct J { a: f64, }
+                    @ver(1) struct K { j: ?J@ver(1), }
+                    @ver(1) struct S { a: {K@ver(1)}, }
+                },
+                vec!["`K@ver(1)` cannot be used in map keys or set elements, as some of its fields cannot", "`J@ver(1)` cannot be used in map keys or set elements, as some of its fields cannot", "float types cannot be used in map keys or set elements"],
+            ),
+            (
+                quote! {
+                    sch "a";
+                    @ver(1) struct K { a: f64, b: u8, }
+                    @ver(2) struct K { @ver(1) { @rem(b), }, }
+                    @ver(1) struct S { a: {K@ver(2)}, }
+                },
+                vec!["`K@ver(2)` cannot be used in map keys or set elements, as some of its fields cannot", "float types cannot be used in map keys or set elements"],
+            ),
+        ] {
+            assert_eq!(error_messages(tokens.clone()), messages, "{}", tokens);
+        }
+
+        assert_eq!(
+            error_messages(quote! {
+                sch "a";
+                @ver(1)
+                struct S {
+                    m: [[f32: u8; ord]: u8; ord],
+                    s: {{f64; ord}; ord},
+                    v: {[u8: f32; ord]; ord},
+                }
+            }),
+            vec![
+                "float types cannot be used in map keys or set elements",
+                "float types cannot be used in map keys or set elements",
+                "float types cannot be used in map keys or set elements",
+            ]
+        );
+    }
+
+    #[test]
+    fn flags() {
+        validate_tokens(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            flags Permissions {
+                Read = 0,
+                Write = 1,
+            }
+
+            @ver(1)
+            struct User {
+                permissions: Permissions@ver(1),
+            }
+        })
+        .unwrap();
+
+        let err = validate_tokens(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            flags Permissions {
+                Read = 0,
+                Read = 1,
+                Write = 0,
+            }
+        })
+        .unwrap_err();
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "`Read` is defined more than once",
+                "`Read` previously defined here",
+                "bit 0 is used by more than one flag",
+                "bit 0 previously used here",
+            ]
+        );
+
+        let err = validate_tokens(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            flags Permissions {
+                Read = 0,
+            }
+
+            @ver(2)
+            flags Permissions {
+                @ver(1) {},
+                Write = 0,
+            }
+        })
+        .unwrap_err();
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "bit 0 is used by more than one flag",
+                "bit 0 previously used here",
+            ]
+        );
+    }
+
+    #[test]
+    fn recursive_types() {
+        validate_tokens(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+            }
+
+            @ver(1)
+            struct Tree {
+                value: u8,
+                parent: ?Tree@ver(1),
+                children: [Tree@ver(1)],
+                by_name: [str: Tree@ver(1)],
+                page: Page<Tree@ver(1)>@ver(1),
+                object: &Object,
+            }
+
+            @ver(1)
+            struct Node {
+                children: {Node@ver(1); ord},
+            }
+
+            @ver(2)
+            struct Tree {
+                @ver(1) {
+                    @rem(parent),
+                },
+            }
+        })
+        .unwrap();
+
+        let err = validate_tokens(quote! {
+            sch "scheme/name";
+
+            type Nodes = [Node@ver(1); 2];
+
+            @ver(1)
+            struct Wrap<T> {
+                inner: T,
+            }
+
+            @ver(1)
+            struct Node {
+                value: u8,
+                wrapped: Wrap<Node@ver(1)>@ver(1),
+            }
+
+            @ver(1)
+            union Expr {
+                Literal(u8),
+                Add((Expr@ver(1), Expr@ver(1))),
+            }
+
+            @ver(1)
+            struct A {
+                b: B@ver(1),
+            }
+
+            @ver(1)
+            struct B (
+                Nodes,
+                C@ver(1),
+            )
+
+            @ver(1)
+            struct C {
+                @ver(2) {},
+            }
+
+            @ver(2)
+            struct C {
+                a: A@ver(1),
+            }
+        })
+        .unwrap_err();
+
+        let messages: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        let hint = "; store it behind `?T`, a list, a map or an object reference";
+        assert_eq!(
+            messages,
+            vec![
+                format!(
+                    "recursive type `Node@ver(1)` has infinite size: Node@ver(1) -> Node@ver(1){}",
+                    hint
+                ),
+                format!(
+                    "recursive type `Expr@ver(1)` has infinite size: Expr@ver(1) -> Expr@ver(1){}",
+                    hint
+                ),
+                format!(
+                    "recursive type `A@ver(1)` has infinite size: A@ver(1) -> B@ver(1) -> C@ver(1) -> A@ver(1){}",
+                    hint
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn extern_types() {
+        let common: Scheme = syn::parse2(quote! {
+            sch "my/common";
+
+            @ver(1)
+            struct Location {
+                lat: f64,
+                lon: f64,
+            }
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+            }
+
+            @ver(1)
+            obj(struct) Group {
+                name: str,
+            }
+
+            @ver(1)
+            fn Locate (
+                name: str,
+            ) -> Location@ver(1)
+        })
+        .unwrap();
+        let find_scheme = |use_: &Use| (use_.segments.last()? == "common").then_some(&common);
+        let validate_externs_tokens =
+            |tokens: proc_macro2::TokenStream| validate_externs(&syn::parse2(tokens)?, find_scheme);
+
+        validate_externs_tokens(quote! {
+            sch "my/users";
+
+            use crate::common;
+            use crate::common as shared;
+
+            @ver(1)
+            struct User {
+                location: common::Location@ver(1),
+                locations: shared::Page<common::Location@ver(1)>@ver(1),
+                group: &common::Group,
+            }
+        })
+        .unwrap();
+        validate_externs_tokens(quote! {
+            sch "my/users";
+
+            use crate::missing;
+
+            @ver(1)
+            struct User {
+                location: missing::Location@ver(1),
+            }
+        })
+        .unwrap();
+
+        for (tokens, messages) in [
+            (
+                quote! { sch "a"; @ver(1) struct S { a: common::Location@ver(1), } },
+                vec!["`common` is not a used scheme"],
+            ),
+            (
+                quote! { sch "a"; use crate::common; @ver(1) struct S { a: common::Missing@ver(1), } },
+                vec!["`common::Missing` is not defined in scheme \"my/common\""],
+            ),
+            (
+                quote! { sch "a"; use crate::common; @ver(1) struct S { a: common::Location@ver(2), } },
+                vec!["`common::Location@ver(2)` is not defined, available versions: 1"],
+            ),
+            (
+                quote! { sch "a"; use crate::common; @ver(1) struct S { a: common::Page@ver(1), } },
+                vec!["`Page@ver(1)` takes 1 generic argument(s) but 0 were given"],
+            ),
+            (
+                quote! { sch "a"; use crate::common; @ver(1) struct S { a: common::Locate@ver(1), } },
+                vec!["`common::Locate@ver(1)` is a function and cannot be used as a field type"],
+            ),
+            (
+                quote! { sch "a"; use crate::common; @ver(1) struct S { a: &common::Location, } },
+                vec!["`common::Location` is not an object type"],
+            ),
+        ] {
+            let err = validate_externs_tokens(tokens.clone()).unwrap_err();
+            let found: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+            assert_eq!(found, messages, "{}", tokens);
+        }
+    }
+
+    #[test]
+    fn extern_types_without_uses() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "my/users";
+
+            @ver(1)
+            struct User {
+                id: nope::Id@ver(1),
+                ids: [nope::Id@ver(1)],
+            }
+        })
+        .unwrap();
+
+        let err = validate_externs(&scheme, |_| None).unwrap_err();
+        let found: Vec<String> = err.into_iter().map(|err| err.to_string()).collect();
+        assert_eq!(
+            found,
+            ["`nope` is not a used scheme", "`nope` is not a used scheme"]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+pub mod text;
+
+/// A value of a scheme type, independent of any generated Rust type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(i128),
+    /// A `u128` value above `i128::MAX`.
+    UInt(u128),
+    Float(f64),
+    Char(char),
+    /// A `str`, or a `timestamp`, `duration`, `uuid` or `decimal` in its
+    /// textual form.
+    String(String),
+    Bytes(Vec<u8>),
+    Option(Option<Box<Value>>),
+    /// The id of the object an `&Object` field refers to.
+    Reference(u64),
+    /// An array, list or set.
+    List(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    /// A tuple, or the fields of a tuple struct or variant.
+    Tuple(Vec<Value>),
+    /// The fields of a struct, variant, command or function's parameters.
+    Struct(Vec<(String, Value)>),
+    /// A unit struct or variant.
+    Unit,
+    /// A union variant or enum field, along with its fields.
+    Variant(String, Box<Value>),
+    /// The names of the set flags of a flags type.
+    Flags(Vec<String>),
+}
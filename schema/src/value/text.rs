@@ -0,0 +1,977 @@
+//! A human readable text format for values, similar to RON.
+//!
+//! ```text
+//! // Comments run to the end of the line.
+//! {
+//!     id: 42,
+//!     name: "Ada",
+//!     email: Some("ada@example.com"),
+//!     avatar: b"\x89PNG",
+//!     tags: ["admin", "staff"],
+//!     scores: { "math": 9.5 },
+//!     position: (1, -2),
+//!     status: Active,
+//!     last_event: Moved { from: (0, 0), to: (1, -2) },
+//!     manager: &7,
+//!     permissions: [Read, Write],
+//! }
+//! ```
+//!
+//! Structs and maps are written in braces, tuples and tuple structs in
+//! parentheses, and lists, arrays, sets and flags in square brackets. Union
+//! variants and enum fields are written by name, followed by their fields.
+//! Optional values are `None` or `Some(value)` and object references are
+//! `&` followed by the object's id. Timestamps, durations, uuids and
+//! decimals are written as strings.
+
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    parse::{
+        ArraySize, ConstValue, FieldType, MajorVersion, Object, Primitive, Scheme, StructBody,
+        StructItem, Tuple, TupleItem, Type, TypeAlias,
+    },
+    resolve::{resolve, Member},
+    value::Value,
+};
+
+/// An error in the text of a value.
+#[derive(Debug, PartialEq)]
+pub struct Error {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// The column of the error in characters, starting at 1.
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Parses `text` as a value of `version` of the type called `name`.
+pub fn parse(scheme: &Scheme, name: &str, version: u16, text: &str) -> Result<Value> {
+    let mut parser = Parser {
+        scheme,
+        aliases: scheme
+            .aliases
+            .iter()
+            .map(|alias| (alias.name.as_str(), alias))
+            .collect(),
+        text,
+        pos: 0,
+    };
+
+    let Some(type_def) = parser.find_type(name, &MajorVersion(version)) else {
+        return Err(parser.error(format!("unknown type `{}@ver({})`", name, version)));
+    };
+    if !type_def.generics().is_empty() {
+        return Err(parser.error(format!(
+            "generic type `{}@ver({})` needs generic arguments",
+            name, version
+        )));
+    }
+
+    let value = parser.type_value(type_def, &Subst::default())?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        return Err(parser.error("expected end of input"));
+    }
+    Ok(value)
+}
+
+/// Prints `value` in the text format.
+pub fn print(value: &Value) -> String {
+    let mut text = String::new();
+    write_value(&mut text, value, 0);
+    text.push('\n');
+    text
+}
+
+/// The generic arguments of the generic type being parsed, which are
+/// parsed with the generic arguments of the type using it.
+#[derive(Default)]
+struct Subst<'s, 'a> {
+    args: Vec<(&'a str, &'a FieldType)>,
+    outer: Option<&'s Subst<'s, 'a>>,
+}
+
+struct Parser<'a> {
+    scheme: &'a Scheme,
+    aliases: HashMap<&'a str, &'a TypeAlias>,
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn find_type(&self, name: &str, version: &MajorVersion) -> Option<&'a Type> {
+        self.scheme
+            .types
+            .iter()
+            .find(|type_def| type_def.name() == name && type_def.version() == version)
+    }
+
+    fn type_value(&mut self, type_def: &'a Type, subst: &Subst<'_, 'a>) -> Result<Value> {
+        match type_def {
+            Type::Struct(struct_) | Type::Object(Object::Struct(struct_)) => {
+                self.body_value(&struct_.name, &struct_.version, &struct_.body, subst)
+            }
+            Type::Union(union_) | Type::Object(Object::Union(union_)) => {
+                let start = self.start();
+                let variant_name = self.ident()?;
+                let members = resolve(self.scheme, &union_.name, &union_.version, &union_.items);
+                let Some(member) = members
+                    .iter()
+                    .find(|member| member.field.name == variant_name)
+                else {
+                    return Err(self.error_at(start, format!("unknown variant `{}`", variant_name)));
+                };
+                let version = member.included_from.unwrap_or(&union_.version);
+                let body = self.body_value(&union_.name, version, &member.field.body, subst)?;
+                Ok(Value::Variant(variant_name, Box::new(body)))
+            }
+            Type::Enum(enum_) | Type::Object(Object::Enum(enum_)) => {
+                let start = self.start();
+                let field_name = self.ident()?;
+                let members = resolve(self.scheme, &enum_.name, &enum_.version, &enum_.items);
+                if !members.iter().any(|member| member.field.name == field_name) {
+                    return Err(self.error_at(start, format!("unknown field `{}`", field_name)));
+                }
+                Ok(Value::Variant(field_name, Box::new(Value::Unit)))
+            }
+            Type::Flags(flags) => {
+                let members = resolve(self.scheme, &flags.name, &flags.version, &flags.items);
+                let mut names: Vec<String> = Vec::new();
+                self.list('[', ']', |parser| {
+                    let start = parser.start();
+                    let name = parser.ident()?;
+                    if !members.iter().any(|member| member.field.name == name) {
+                        return Err(parser.error_at(start, format!("unknown flag `{}`", name)));
+                    }
+                    if names.contains(&name) {
+                        return Err(parser.error_at(start, format!("duplicate flag `{}`", name)));
+                    }
+                    names.push(name);
+                    Ok(())
+                })?;
+                Ok(Value::Flags(names))
+            }
+            Type::Function(function) => {
+                self.struct_value(&function.name, &function.version, &function.items, subst)
+            }
+            Type::Command(command) => {
+                self.struct_value(&command.name, &command.version, &command.items, subst)
+            }
+        }
+    }
+
+    fn body_value(
+        &mut self,
+        name: &str,
+        version: &MajorVersion,
+        body: &'a StructBody,
+        subst: &Subst<'_, 'a>,
+    ) -> Result<Value> {
+        match body {
+            StructBody::Items(items) => self.struct_value(name, version, items, subst),
+            StructBody::Tuple(Tuple(items)) => {
+                let members = resolve(self.scheme, name, version, items);
+                let required = members
+                    .iter()
+                    .filter(|member| {
+                        member.included_from.is_some() || member.field.added().is_none()
+                    })
+                    .count();
+                let start = self.start();
+                let mut values = Vec::new();
+                self.list('(', ')', |parser| {
+                    let Some(member) = members.get(values.len()) else {
+                        return Err(
+                            parser.error(format!("expected at most {} fields", members.len()))
+                        );
+                    };
+                    values.push(parser.field_value(&member.field.field_type, subst)?);
+                    Ok(())
+                })?;
+                if values.len() < required {
+                    return Err(
+                        self.error_at(start, format!("expected at least {} fields", required))
+                    );
+                }
+                Ok(Value::Tuple(values))
+            }
+            StructBody::Unit => {
+                if self.peek() == Some('(') {
+                    self.list('(', ')', |parser| Err(parser.error("expected `)`")))?;
+                }
+                Ok(Value::Unit)
+            }
+        }
+    }
+
+    fn struct_value(
+        &mut self,
+        name: &str,
+        version: &MajorVersion,
+        items: &'a [StructItem],
+        subst: &Subst<'_, 'a>,
+    ) -> Result<Value> {
+        let members = resolve(self.scheme, name, version, items);
+        let mut fields: Vec<Option<Value>> = members.iter().map(|_| None).collect();
+        self.list('{', '}', |parser| {
+            let start = parser.start();
+            let field_name = parser.ident()?;
+            let Some(index) = members
+                .iter()
+                .position(|member| member.field.name == field_name)
+            else {
+                return Err(parser.error_at(start, format!("unknown field `{}`", field_name)));
+            };
+            if fields[index].is_some() {
+                return Err(parser.error_at(start, format!("duplicate field `{}`", field_name)));
+            }
+            parser.expect(':')?;
+            fields[index] = Some(parser.field_value(&members[index].field.field_type, subst)?);
+            Ok(())
+        })?;
+
+        let mut values = Vec::new();
+        for (member, value) in members.iter().zip(fields) {
+            match value {
+                Some(value) => values.push((member.field.name.clone(), value)),
+                None if member.included_from.is_none() && member.field.added().is_some() => {}
+                None => {
+                    // Point at the closing brace.
+                    return Err(self.error_at(
+                        self.pos - 1,
+                        format!("missing field `{}`", member.field.name),
+                    ));
+                }
+            }
+        }
+        Ok(Value::Struct(values))
+    }
+
+    fn field_value(&mut self, field_type: &'a FieldType, subst: &Subst<'_, 'a>) -> Result<Value> {
+        match field_type {
+            FieldType::Primitive(_, primitive) => self.primitive_value(primitive),
+            FieldType::Type(_, name, None, generic_args, version) => {
+                if version.is_none() && generic_args.is_empty() {
+                    if let Some((_, arg)) = subst.args.iter().find(|(param, _)| param == name) {
+                        let top_level = Subst::default();
+                        return self.field_value(arg, subst.outer.unwrap_or(&top_level));
+                    }
+                    if let Some(alias) = self.aliases.get(name.as_str()) {
+                        return self.field_value(&alias.field_type, &Subst::default());
+                    }
+                }
+
+                let type_def = version
+                    .as_ref()
+                    .and_then(|version| self.find_type(name, version));
+                let Some(type_def) = type_def else {
+                    return Err(self.error(format!("unknown type `{}`", name)));
+                };
+                let generic_subst = Subst {
+                    args: type_def
+                        .generics()
+                        .iter()
+                        .map(|param| param.name.as_str())
+                        .zip(generic_args)
+                        .collect(),
+                    outer: Some(subst),
+                };
+                self.type_value(type_def, &generic_subst)
+            }
+            FieldType::Type(_, scheme_alias, Some(name), _, _) => Err(self.error(format!(
+                "values of `{}::{}` cannot be parsed without its scheme",
+                scheme_alias, name
+            ))),
+            FieldType::Optional(_, inner) => {
+                let start = self.start();
+                match self.ident()?.as_str() {
+                    "None" => Ok(Value::Option(None)),
+                    "Some" => {
+                        self.expect('(')?;
+                        let value = self.field_value(inner, subst)?;
+                        self.expect(')')?;
+                        Ok(Value::Option(Some(Box::new(value))))
+                    }
+                    _ => Err(self.error_at(start, "expected `None` or `Some`")),
+                }
+            }
+            FieldType::Reference(..) => {
+                self.expect('&')?;
+                let start = self.start();
+                let id = self.int()?;
+                u64::try_from(id)
+                    .map(Value::Reference)
+                    .map_err(|_| self.error_at(start, "object id out of range"))
+            }
+            FieldType::Array(_, inner, size) => {
+                let start = self.start();
+                let values = self.list_value(inner, subst)?;
+                let size = match size {
+                    ArraySize::Value(size) => Some(*size as usize),
+                    ArraySize::Const(_, name) => self
+                        .scheme
+                        .consts
+                        .iter()
+                        .find(|const_| &const_.name == name)
+                        .and_then(|const_| match const_.value {
+                            ConstValue::Int(size) => usize::try_from(size).ok(),
+                            _ => None,
+                        }),
+                };
+                match size {
+                    Some(size) if size != values.len() => Err(self.error_at(
+                        start,
+                        format!("expected {} elements, found {}", size, values.len()),
+                    )),
+                    _ => Ok(Value::List(values)),
+                }
+            }
+            FieldType::List(_, inner) | FieldType::Set(_, inner, _) => {
+                Ok(Value::List(self.list_value(inner, subst)?))
+            }
+            FieldType::Map(_, key_type, value_type, _) => {
+                let mut entries = Vec::new();
+                self.list('{', '}', |parser| {
+                    let key = parser.field_value(key_type, subst)?;
+                    parser.expect(':')?;
+                    let value = parser.field_value(value_type, subst)?;
+                    entries.push((key, value));
+                    Ok(())
+                })?;
+                Ok(Value::Map(entries))
+            }
+            FieldType::Tuple(_, Tuple(items)) => {
+                let field_types: Vec<&FieldType> = items
+                    .iter()
+                    .filter_map(|item| match item {
+                        TupleItem::Field(field) => Some(&field.field_type),
+                        TupleItem::Include(_) => None,
+                    })
+                    .collect();
+                let start = self.start();
+                let mut values = Vec::new();
+                self.list('(', ')', |parser| {
+                    let Some(field_type) = field_types.get(values.len()) else {
+                        return Err(
+                            parser.error(format!("expected {} elements", field_types.len()))
+                        );
+                    };
+                    values.push(parser.field_value(field_type, subst)?);
+                    Ok(())
+                })?;
+                if values.len() != field_types.len() {
+                    return Err(
+                        self.error_at(start, format!("expected {} elements", field_types.len()))
+                    );
+                }
+                Ok(Value::Tuple(values))
+            }
+        }
+    }
+
+    fn list_value(&mut self, inner: &'a FieldType, subst: &Subst<'_, 'a>) -> Result<Vec<Value>> {
+        let mut values = Vec::new();
+        self.list('[', ']', |parser| {
+            values.push(parser.field_value(inner, subst)?);
+            Ok(())
+        })?;
+        Ok(values)
+    }
+
+    fn primitive_value(&mut self, primitive: &Primitive) -> Result<Value> {
+        self.skip_whitespace();
+        let start = self.start();
+        if let Primitive::UInt128 = primitive {
+            let number = self.number().replace('_', "");
+            return match number.parse::<u128>() {
+                Ok(value) => Ok(i128::try_from(value).map_or(Value::UInt(value), Value::Int)),
+                Err(_) if number.parse::<i128>().is_ok() => {
+                    Err(self.error_at(start, "integer out of range"))
+                }
+                Err(_) => Err(self.error_at(start, "expected an integer")),
+            };
+        }
+        if let Some((min, max)) = primitive.int_range() {
+            let value = self.int()?;
+            if value < min || value > max {
+                return Err(self.error_at(start, "integer out of range"));
+            }
+            return Ok(Value::Int(value));
+        }
+
+        match primitive {
+            Primitive::Float32 | Primitive::Float64 => {
+                let number = self.number();
+                number
+                    .replace('_', "")
+                    .parse()
+                    .map(Value::Float)
+                    .map_err(|_| self.error_at(start, "expected a number"))
+            }
+            Primitive::Boolean => match self.ident()?.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => Err(self.error_at(start, "expected `true` or `false`")),
+            },
+            Primitive::Char => {
+                let chars = self.quoted('\'', false)?;
+                match chars[..] {
+                    [c] => Ok(Value::Char(c)),
+                    _ => Err(self.error_at(start, "expected a single character")),
+                }
+            }
+            Primitive::String
+            | Primitive::Timestamp
+            | Primitive::Duration
+            | Primitive::Uuid
+            | Primitive::Decimal => {
+                let chars = self.quoted('"', false)?;
+                Ok(Value::String(chars.into_iter().collect()))
+            }
+            Primitive::Bytes => {
+                if !self.text[self.pos..].starts_with('b') {
+                    return Err(self.error("expected a byte string"));
+                }
+                self.pos += 1;
+                let bytes = self.quoted('"', true)?;
+                // Byte strings only hold ASCII characters and `\x` escapes.
+                Ok(Value::Bytes(bytes.into_iter().map(|b| b as u8).collect()))
+            }
+            Primitive::Any => Err(self.error("values of `any` cannot be written as text")),
+            _ => unreachable!("integer primitives are handled above"),
+        }
+    }
+
+    /// Parses the items of a list delimited by `open` and `close`, separated
+    /// by commas with an optional trailing comma.
+    fn list(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        self.expect(open)?;
+        loop {
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(());
+            }
+            item(self)?;
+            if self.peek() == Some(',') {
+                self.pos += 1;
+            } else if self.peek() != Some(close) {
+                return Err(self.error(format!("expected `,` or `{}`", close)));
+            }
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            let rest = &self.text[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Returns the position of the next token.
+    fn start(&mut self) -> usize {
+        self.skip_whitespace();
+        self.pos
+    }
+
+    /// Returns the next character after any whitespace.
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.text[self.pos..].chars().next()
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`", c)))
+        }
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        self.skip_whitespace();
+        let rest = &self.text[self.pos..];
+        let len = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        if len == 0 || rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(self.error("expected an identifier"));
+        }
+        self.pos += len;
+        Ok(rest[..len].to_string())
+    }
+
+    /// Returns the text of the number starting at the current position.
+    fn number(&mut self) -> &'a str {
+        self.skip_whitespace();
+        let rest = &self.text[self.pos..];
+        let mut len = 0;
+        let mut prev = None;
+        for c in rest.chars() {
+            let is_sign = c == '-' || c == '+';
+            if !(c.is_ascii_alphanumeric()
+                || c == '_'
+                || c == '.'
+                || (is_sign && (len == 0 || matches!(prev, Some('e' | 'E')))))
+            {
+                break;
+            }
+            len += c.len_utf8();
+            prev = Some(c);
+        }
+        self.pos += len;
+        &rest[..len]
+    }
+
+    fn int(&mut self) -> Result<i128> {
+        self.skip_whitespace();
+        let start = self.start();
+        let number = self.number();
+        number
+            .replace('_', "")
+            .parse()
+            .map_err(|_| self.error_at(start, "expected an integer"))
+    }
+
+    /// Parses a quoted literal, returning its characters, which are bytes if
+    /// `is_bytes` is set.
+    fn quoted(&mut self, quote: char, is_bytes: bool) -> Result<Vec<char>> {
+        if !self.text[self.pos..].starts_with(quote) {
+            return Err(self.error(format!("expected `{}`", quote)));
+        }
+        self.pos += 1;
+
+        let mut chars = Vec::new();
+        loop {
+            let start = self.pos;
+            let Some(c) = self.text[self.pos..].chars().next() else {
+                return Err(self.error("unterminated literal"));
+            };
+            self.pos += c.len_utf8();
+            let c = match c {
+                c if c == quote => return Ok(chars),
+                '\\' => self.escape(is_bytes)?,
+                c if is_bytes && !c.is_ascii() => {
+                    return Err(self.error_at(start, "non-ASCII character in byte string"));
+                }
+                c => c,
+            };
+            chars.push(c);
+        }
+    }
+
+    fn escape(&mut self, is_bytes: bool) -> Result<char> {
+        let start = self.pos - 1;
+        let Some(c) = self.text[self.pos..].chars().next() else {
+            return Err(self.error("unterminated literal"));
+        };
+        self.pos += c.len_utf8();
+
+        let hex = |parser: &mut Self, len: usize| {
+            let digits = parser.text.get(parser.pos..parser.pos + len);
+            let value = digits
+                .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                .and_then(char::from_u32);
+            parser.pos += len;
+            value.ok_or_else(|| parser.error_at(start, "invalid escape"))
+        };
+        match c {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0' => Ok('\0'),
+            '\\' | '\'' | '"' => Ok(c),
+            'x' if is_bytes => hex(self, 2),
+            'u' if !is_bytes && self.text[self.pos..].starts_with('{') => {
+                self.pos += 1;
+                let len = self.text[self.pos..].find('}').unwrap_or(0);
+                let value = hex(self, len)?;
+                // Skip the closing brace.
+                self.pos += 1;
+                Ok(value)
+            }
+            _ => Err(self.error_at(start, "invalid escape")),
+        }
+    }
+
+    fn error(&mut self, message: impl Into<String>) -> Error {
+        self.skip_whitespace();
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> Error {
+        let before = &self.text[..pos];
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        Error {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        }
+    }
+}
+
+fn write_value(text: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Bool(value) => text.push_str(&value.to_string()),
+        Value::Int(value) => text.push_str(&value.to_string()),
+        Value::UInt(value) => text.push_str(&value.to_string()),
+        Value::Float(value) if value.is_nan() => text.push_str("NaN"),
+        Value::Float(value) => text.push_str(&format!("{:?}", value)),
+        Value::Char(value) => {
+            text.push('\'');
+            write_escaped(text, *value, '\'', false);
+            text.push('\'');
+        }
+        Value::String(value) => {
+            text.push('"');
+            for c in value.chars() {
+                write_escaped(text, c, '"', false);
+            }
+            text.push('"');
+        }
+        Value::Bytes(value) => {
+            text.push_str("b\"");
+            for b in value {
+                write_escaped(text, *b as char, '"', true);
+            }
+            text.push('"');
+        }
+        Value::Option(None) => text.push_str("None"),
+        Value::Option(Some(value)) => {
+            text.push_str("Some(");
+            write_value(text, value, indent);
+            text.push(')');
+        }
+        Value::Reference(id) => text.push_str(&format!("&{}", id)),
+        Value::List(values) => write_items(text, '[', ']', values, indent),
+        Value::Tuple(values) => write_items(text, '(', ')', values, indent),
+        Value::Map(entries) => write_entries(
+            text,
+            entries
+                .iter()
+                .map(|(key, value)| (Entry::Value(key), value)),
+            indent,
+        ),
+        Value::Struct(fields) => write_entries(
+            text,
+            fields
+                .iter()
+                .map(|(name, value)| (Entry::Name(name), value)),
+            indent,
+        ),
+        Value::Unit => text.push_str("()"),
+        Value::Variant(name, body) => {
+            text.push_str(name);
+            match &**body {
+                Value::Unit => {}
+                Value::Struct(_) => {
+                    text.push(' ');
+                    write_value(text, body, indent);
+                }
+                body => write_value(text, body, indent),
+            }
+        }
+        Value::Flags(names) => {
+            text.push('[');
+            text.push_str(&names.join(", "));
+            text.push(']');
+        }
+    }
+}
+
+fn write_items(text: &mut String, open: char, close: char, values: &[Value], indent: usize) {
+    let is_simple = values.iter().all(|value| {
+        !matches!(
+            value,
+            Value::List(_)
+                | Value::Tuple(_)
+                | Value::Map(_)
+                | Value::Struct(_)
+                | Value::Variant(..)
+        )
+    });
+
+    text.push(open);
+    if is_simple {
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 {
+                text.push_str(", ");
+            }
+            write_value(text, value, indent);
+        }
+    } else {
+        for value in values {
+            text.push('\n');
+            text.push_str(&"    ".repeat(indent + 1));
+            write_value(text, value, indent + 1);
+            text.push(',');
+        }
+        text.push('\n');
+        text.push_str(&"    ".repeat(indent));
+    }
+    text.push(close);
+}
+
+enum Entry<'a> {
+    Name(&'a str),
+    Value(&'a Value),
+}
+
+fn write_entries<'a>(
+    text: &mut String,
+    entries: impl ExactSizeIterator<Item = (Entry<'a>, &'a Value)>,
+    indent: usize,
+) {
+    if entries.len() == 0 {
+        text.push_str("{}");
+        return;
+    }
+
+    text.push('{');
+    for (key, value) in entries {
+        text.push('\n');
+        text.push_str(&"    ".repeat(indent + 1));
+        match key {
+            Entry::Name(name) => text.push_str(name),
+            Entry::Value(key) => write_value(text, key, indent + 1),
+        }
+        text.push_str(": ");
+        write_value(text, value, indent + 1);
+        text.push(',');
+    }
+    text.push('\n');
+    text.push_str(&"    ".repeat(indent));
+    text.push('}');
+}
+
+fn write_escaped(text: &mut String, c: char, quote: char, is_bytes: bool) {
+    match c {
+        '\n' => text.push_str("\\n"),
+        '\r' => text.push_str("\\r"),
+        '\t' => text.push_str("\\t"),
+        '\0' => text.push_str("\\0"),
+        '\\' => text.push_str("\\\\"),
+        c if c == quote => {
+            text.push('\\');
+            text.push(c);
+        }
+        c if is_bytes && !(c.is_ascii_graphic() || c == ' ') => {
+            text.push_str(&format!("\\x{:02x}", c as u32))
+        }
+        c if c.is_control() => text.push_str(&format!("\\u{{{:x}}}", c as u32)),
+        c => text.push(c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    fn scheme() -> Scheme {
+        syn::parse2(quote! {
+            sch "my/users";
+
+            const NAME_LEN: u32 = 2;
+            type Id = u64;
+
+            @ver(1)
+            struct Page<T> {
+                items: [T],
+                next: ?Id,
+            }
+
+            @ver(1)
+            struct Point (
+                i32,
+                i32,
+            )
+
+            @ver(1)
+            flags Permissions {
+                Read = 0,
+                Write = 1,
+            }
+
+            @ver(1)
+            enum Status {
+                Active,
+                Banned,
+            }
+
+            @ver(1)
+            union Event {
+                Joined,
+                Moved {
+                    from: Point@ver(1),
+                    to: Point@ver(1),
+                },
+                Renamed(str),
+            }
+
+            @ver(1)
+            struct User {
+                id: Id,
+                initials: [char; NAME_LEN],
+                email: ?str,
+                avatar: bytes,
+                scores: [str: f64],
+                status: Status@ver(1),
+                events: Page<Event@ver(1)>@ver(1),
+                manager: &User,
+                permissions: Permissions@ver(1),
+                @add(1.1)
+                nickname: str,
+            }
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_and_prints() {
+        let scheme = scheme();
+        let value = parse(
+            &scheme,
+            "User",
+            1,
+            r#"
+            // A hand written user.
+            {
+                id: 42,
+                initials: ['A', 'L'],
+                email: Some("ada@example.com"),
+                avatar: b"\x89PNG",
+                scores: { "math": 9.5, "tab\t": -1e3 },
+                status: Active,
+                events: {
+                    items: [Joined, Renamed("Ada \u{1F600}"), Moved { from: (0, 0), to: (1, -2) }],
+                    next: None,
+                },
+                manager: &7,
+                permissions: [Write, Read],
+            }
+            "#,
+        )
+        .unwrap();
+
+        let text = print(&value);
+        assert_eq!(
+            text,
+            r#"{
+    id: 42,
+    initials: ['A', 'L'],
+    email: Some("ada@example.com"),
+    avatar: b"\x89PNG",
+    scores: {
+        "math": 9.5,
+        "tab\t": -1000.0,
+    },
+    status: Active,
+    events: {
+        items: [
+            Joined,
+            Renamed("Ada 😀"),
+            Moved {
+                from: (0, 0),
+                to: (1, -2),
+            },
+        ],
+        next: None,
+    },
+    manager: &7,
+    permissions: [Write, Read],
+}
+"#
+        );
+        assert_eq!(parse(&scheme, "User", 1, &text), Ok(value));
+    }
+
+    #[test]
+    fn round_trips_u128() {
+        let scheme: Scheme = syn::parse2(quote! {
+            sch "scheme/name";
+
+            @ver(1)
+            struct Big (u128, u128)
+        })
+        .unwrap();
+
+        let text = "(340282366920938463463374607431768211455, 7)\n";
+        let value = parse(&scheme, "Big", 1, text).unwrap();
+        assert_eq!(
+            value,
+            Value::Tuple(vec![Value::UInt(u128::MAX), Value::Int(7)])
+        );
+        assert_eq!(print(&value), text);
+        assert_eq!(
+            parse(&scheme, "Big", 1, "(-1, 0)").unwrap_err().to_string(),
+            "1:2: integer out of range"
+        );
+    }
+
+    #[test]
+    fn reports_errors() {
+        let scheme = scheme();
+        let error = |name: &str, text: &str| parse(&scheme, name, 1, text).unwrap_err().to_string();
+
+        assert_eq!(
+            error("Point", "(1, 2, 3)"),
+            "1:8: expected at most 2 fields"
+        );
+        assert_eq!(error("Point", "(1)"), "1:1: expected at least 2 fields");
+        assert_eq!(
+            error("Point", "(1, 2147483648)"),
+            "1:5: integer out of range"
+        );
+        assert_eq!(
+            error("Status", "\n  Retired"),
+            "2:3: unknown field `Retired`"
+        );
+        assert_eq!(
+            error("Event", "Moved { from: (0, 0) }"),
+            "1:22: missing field `to`"
+        );
+        assert_eq!(
+            error("Permissions", "[Read, Read]"),
+            "1:8: duplicate flag `Read`"
+        );
+        assert_eq!(
+            error("Event", "Renamed(\"abc)"),
+            "1:14: unterminated literal"
+        );
+        assert_eq!(
+            error("Event", "Joined Joined"),
+            "1:8: expected end of input"
+        );
+        assert_eq!(
+            error("Page", "{}"),
+            "1:1: generic type `Page@ver(1)` needs generic arguments"
+        );
+        assert_eq!(
+            error("User", "{ id: 1, initials: ['A'] }"),
+            "1:20: expected 2 elements, found 1"
+        );
+    }
+}
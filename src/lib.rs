@@ -1,8 +1,160 @@
+use cycle_schema::{
+    parse::{Scheme, Use},
+    validate,
+};
 use proc_macro::TokenStream;
+use proc_macro2::{Delimiter, Group, Ident, Span};
+use quote::{format_ident, quote};
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input, Result,
+};
 
-mod parse;
-
+/// Defines a scheme.
+///
+/// Besides its types, a scheme exports a hidden `__cycle_scheme!` macro from
+/// the module `define!` is invoked in, which other schemes use to look it up.
+/// A scheme brought in with `use a::b;` is found by invoking
+/// `a::b::__cycle_scheme!`, so the use path is the path of the module the
+/// used scheme is defined in, and each module defines at most one scheme.
 #[proc_macro]
 pub fn define(tokens: TokenStream) -> TokenStream {
-    todo!()
+    let tokens = proc_macro2::TokenStream::from(tokens);
+    let scheme = match syn::parse2::<Scheme>(tokens.clone()) {
+        Ok(scheme) => scheme,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if let Err(err) = validate::validate(&scheme) {
+        return err.to_compile_error().into();
+    }
+
+    let input = Group::new(Delimiter::Brace, tokens);
+    let Some(use_) = scheme.uses.first() else {
+        // Without uses, this reports every type naming a scheme.
+        if let Err(err) = validate::validate_externs(&scheme, |_| None) {
+            return err.to_compile_error().into();
+        }
+        return expand(&scheme, &input).into();
+    };
+    lookup_scheme(use_, &input, &[]).into()
+}
+
+/// Continues `define!` once the scheme of one more of its `use` items has
+/// been looked up. Not public API.
+///
+/// The input is the scheme being defined, the schemes looked up so far, and
+/// the scheme just looked up, each in braces: `{..} [{..} ..] {..}`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn __define_with_uses(tokens: TokenStream) -> TokenStream {
+    let WithUses { input, used } = parse_macro_input!(tokens as WithUses);
+    let scheme = match syn::parse2::<Scheme>(input.stream()) {
+        Ok(scheme) => scheme,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if let Some(use_) = scheme.uses.get(used.len()) {
+        return lookup_scheme(use_, &input, &used).into();
+    }
+
+    let used_schemes = match used
+        .iter()
+        .map(|group| syn::parse2::<Scheme>(group.stream()))
+        .collect::<Result<Vec<_>>>()
+    {
+        Ok(used_schemes) => used_schemes,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let find_scheme = |use_: &Use| {
+        let index = scheme
+            .uses
+            .iter()
+            .position(|scheme_use| std::ptr::eq(scheme_use, use_))?;
+        used_schemes.get(index)
+    };
+    if let Err(err) = validate::validate_externs(&scheme, find_scheme) {
+        return err.to_compile_error().into();
+    }
+
+    expand(&scheme, &input).into()
+}
+
+struct WithUses {
+    input: Group,
+    used: Vec<Group>,
+}
+
+impl Parse for WithUses {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let scheme;
+        braced!(scheme in input);
+        let scheme = Group::new(Delimiter::Brace, scheme.parse()?);
+
+        let content;
+        bracketed!(content in input);
+        let mut used = Vec::new();
+        while !content.is_empty() {
+            let used_scheme;
+            braced!(used_scheme in content);
+            used.push(Group::new(Delimiter::Brace, used_scheme.parse()?));
+        }
+
+        let looked_up;
+        braced!(looked_up in input);
+        used.push(Group::new(Delimiter::Brace, looked_up.parse()?));
+        Ok(Self {
+            input: scheme,
+            used,
+        })
+    }
+}
+
+/// Invokes the `__cycle_scheme!` macro of the scheme `use_` brings into
+/// scope, which passes the scheme on to `__define_with_uses!`.
+fn lookup_scheme(use_: &Use, input: &Group, used: &[Group]) -> proc_macro2::TokenStream {
+    // `Use` has no spans, so the path resolves where the scheme was written.
+    let span = input
+        .stream()
+        .into_iter()
+        .next()
+        .map_or_else(Span::call_site, |token| token.span());
+    let segments = use_
+        .segments
+        .iter()
+        .map(|segment| Ident::new(segment, span));
+    quote! {
+        #(#segments::)*__cycle_scheme! {
+            { ::cycle_define::__define_with_uses }
+            { #input [#(#used)*] }
+        }
+    }
+}
+
+/// Returns the items `define!` expands to once `scheme` has been validated.
+/// `input` is the scheme's tokens in braces.
+fn expand(scheme: &Scheme, input: &Group) -> proc_macro2::TokenStream {
+    let name: String = scheme
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let macro_name = format_ident!("__cycle_scheme_{}", name);
+    let scheme_tokens = input.stream();
+
+    // The macro passes the scheme to `$callback!` after `$args`, so that
+    // callbacks can thread their own state through it. The scheme's `use`
+    // paths are only ever parsed, never resolved, inside the macro.
+    quote! {
+        #[doc(hidden)]
+        #[macro_export]
+        #[allow(clippy::crate_in_macro_def)]
+        macro_rules! #macro_name {
+            ({ $($callback:tt)* } { $($args:tt)* }) => {
+                $($callback)*! { $($args)* { #scheme_tokens } }
+            };
+        }
+
+        #[doc(hidden)]
+        pub use #macro_name as __cycle_scheme;
+    }
 }
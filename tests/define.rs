@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use cycle_schema::parse::Scheme;
+
+mod common {
+    cycle_define::define! {
+        sch "tests/common";
+
+        @ver(1)
+        struct Location {
+            lat: f64,
+            lon: f64,
+        }
+    }
+}
+
+mod users {
+    cycle_define::define! {
+        sch "tests/users";
+
+        use crate::common;
+        use super::common as shared;
+
+        @ver(1)
+        struct User {
+            home: common::Location@ver(1),
+            work: ?shared::Location@ver(1),
+        }
+    }
+}
+
+/// Parses the scheme `__cycle_scheme!` passes to `stringify`.
+fn parse_exported(exported: &str) -> Scheme {
+    let tokens = proc_macro2::TokenStream::from_str(exported).unwrap();
+    let Some(proc_macro2::TokenTree::Group(group)) = tokens.into_iter().next() else {
+        panic!("expected the scheme in braces: {}", exported);
+    };
+    syn::parse2(group.stream()).unwrap()
+}
+
+#[test]
+fn exports_scheme_metadata() {
+    let common = parse_exported(common::__cycle_scheme!({ stringify } {}));
+    assert_eq!(common.name, "tests/common");
+    assert_eq!(common.types.len(), 1);
+
+    let users = parse_exported(users::__cycle_scheme!({ stringify } {}));
+    assert_eq!(users.name, "tests/users");
+    assert_eq!(users.uses.len(), 2);
+}